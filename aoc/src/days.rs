@@ -0,0 +1,23 @@
+use aoc_common::Solver;
+
+/// Every registered day's solution, in order.
+pub fn all() -> Vec<&'static dyn Solver> {
+    vec![
+        &c01::Day,
+        &c02::Day,
+        &c03::Day,
+        &c04::Day,
+        &c05::Day,
+        &c06::Day,
+        &c07::Day,
+        &c08::Day,
+        &c09::Day,
+        &c10::Day,
+    ]
+}
+
+/// Look up a day's solution by number. Accepts e.g. `5`, `05` or `c05`.
+pub fn find(day: &str) -> Option<&'static dyn Solver> {
+    let day: u32 = day.trim_start_matches('c').parse().ok()?;
+    all().into_iter().find(|solver| solver.day() == day)
+}
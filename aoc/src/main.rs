@@ -0,0 +1,146 @@
+use std::env;
+use std::process;
+
+use aoc_common::{diff, fetch, solver, Solver};
+
+mod days;
+mod new;
+
+const USAGE: &str = " COMMAND [ARGS]
+
+Run Advent of Code 2021 solutions.
+
+COMMANDS:
+ list [-v]: Print each day's name and description. With -v, print the full
+            description instead of the first paragraph.
+ run DAY [OPTIONS] FILENAME: Run the given day's solution. Use `run DAY -h` to
+            see the options for that day.
+ gen DAY [OPTIONS]: Print a random input for the given day. Use `gen DAY -h`
+            to see the options for that day.
+ fetch DAY [OPTIONS]: Download the given day's input into the cache, where
+            `run` finds it when no FILENAME is given. Use `fetch DAY -h` for
+            details.
+ diff DAY [OPTIONS]: Check the given day's alternative implementations against
+            its reference solution on random inputs, printing a minimized
+            input for any disagreement. Use `diff DAY -h` for the options.
+ new DAY NAME: Start a new day: create its crate from a template, with the
+            puzzle's NAME and URL filled in, and register it with the
+            workspace and this runner.
+
+OPTIONS:
+ -h: Print this usage message and exit
+";
+
+fn list(verbose: bool) {
+    for solver in days::all() {
+        println!("{:2}  {}", solver.day(), solver.name());
+        let text = if verbose {
+            solver::description(solver.usage())
+        } else {
+            solver::summary(solver.usage())
+        };
+        for line in text.lines() {
+            println!("    {}", line);
+        }
+    }
+}
+
+/// Look up the day given as the first argument to `command`, and pass it to
+/// `f` along with the program name to use in messages.
+fn with_day<F>(argv0: &str, command: &str, args: &[String], f: F) -> i32
+    where F: FnOnce(&'static dyn Solver, &str) -> i32
+{
+    let day = match args.first() {
+        Some(day) => day,
+        None => {
+            eprintln!("{}: {} requires a DAY", argv0, command);
+            return 2;
+        },
+    };
+    match days::find(day) {
+        Some(solver) => f(solver, &format!("{} {} {}", argv0, command, solver.day())),
+        None => {
+            eprintln!("{}: no solution for day {}", argv0, day);
+            2
+        },
+    }
+}
+
+fn run(argv0: &str, args: &[String]) -> i32 {
+    with_day(argv0, "run", args, |solver, argv0| solver::run(solver, argv0, args[1..].iter().cloned()))
+}
+
+fn gen(argv0: &str, args: &[String]) -> i32 {
+    with_day(argv0, "gen", args, |solver, argv0| solver::gen(solver, argv0, args[1..].iter().cloned()))
+}
+
+fn fetch(argv0: &str, args: &[String]) -> i32 {
+    with_day(argv0, "fetch", args, |solver, argv0| fetch::main(solver, argv0, args[1..].iter().cloned()))
+}
+
+fn diff(argv0: &str, args: &[String]) -> i32 {
+    with_day(argv0, "diff", args, |solver, argv0| diff::main(solver, argv0, args[1..].iter().cloned()))
+}
+
+fn new_day(argv0: &str, args: &[String]) -> i32 {
+    let (day, name) = match args {
+        [day, name] => (day, name),
+        _ => {
+            eprintln!("{}: new requires a DAY and a NAME", argv0);
+            return 2;
+        },
+    };
+    let day = match day.trim_start_matches('c').parse::<u32>() {
+        Ok(day) if (1..=25).contains(&day) => day,
+        _ => {
+            eprintln!("{}: invalid day {:?}", argv0, day);
+            return 2;
+        },
+    };
+    let root = match new::find_root() {
+        Some(root) => root,
+        None => {
+            eprintln!("{}: couldn't find the workspace's Cargo.toml", argv0);
+            return 1;
+        },
+    };
+    match new::scaffold(&root, day, name) {
+        Ok(files) => {
+            for file in files {
+                println!("Created {}", file.strip_prefix(&root).unwrap_or(&file).display());
+            }
+            println!("Paste the puzzle's example into {}/src/example.txt and fill in the TODOs.", new::crate_name(day));
+            0
+        },
+        Err(err) => {
+            eprintln!("{}", err);
+            1
+        },
+    }
+}
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    let code = match args.get(1).map(|s| s.as_str()) {
+        Some("list") => {
+            list(args.iter().skip(2).any(|arg| arg == "-v"));
+            0
+        },
+        Some("run") => run(&args[0], &args[2..]),
+        Some("gen") => gen(&args[0], &args[2..]),
+        Some("fetch") => fetch(&args[0], &args[2..]),
+        Some("diff") => diff(&args[0], &args[2..]),
+        Some("new") => new_day(&args[0], &args[2..]),
+        Some("-h") | Some("--help") => {
+            aoc_common::usage(&args[0], USAGE);
+            0
+        },
+        _ => {
+            aoc_common::usage(&args[0], USAGE);
+            2
+        },
+    };
+    if code != 0 {
+        process::exit(code);
+    }
+}
@@ -0,0 +1,161 @@
+use std::env;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use aoc_common::Error;
+
+const CARGO_TOML: &str = include_str!("../templates/Cargo.toml.tmpl");
+const MAIN_RS: &str = include_str!("../templates/main.rs.tmpl");
+const LIB_RS: &str = include_str!("../templates/lib.rs.tmpl");
+const GEN_RS: &str = include_str!("../templates/gen.rs.tmpl");
+
+/// The name of a day's crate, e.g. `c05`.
+pub fn crate_name(day: u32) -> String {
+    format!("c{:02}", day)
+}
+
+/// Fill in a template's placeholders.
+fn render(template: &str, day: u32, name: &str) -> String {
+    template.replace("@CRATE@", &crate_name(day))
+        .replace("@DAY@", &day.to_string())
+        .replace("@NAME@", &format!("{:?}", name))
+}
+
+/// The workspace root: the nearest directory at or above the current one
+/// whose Cargo.toml has a `[workspace]` section.
+pub fn find_root() -> Option<PathBuf> {
+    let cwd = env::current_dir().ok()?;
+    cwd.ancestors()
+        .find(|dir| fs::read_to_string(dir.join("Cargo.toml")).map(|toml| toml.contains("[workspace]")).unwrap_or(false))
+        .map(|dir| dir.to_path_buf())
+}
+
+/// Insert `line` among the lines that name a day's crate, keeping them in
+/// order by crate name. `crate_of` picks the crate name out of a line, if it
+/// has one. Returns `None` if there are no such lines to insert among, and
+/// the text unchanged if `line`'s crate is already there.
+fn register(text: &str, line: &str, crate_of: fn(&str) -> Option<&str>) -> Option<String> {
+    let new_crate = crate_of(line)?;
+    let mut lines: Vec<&str> = text.lines().collect();
+    let entries: Vec<(usize, &str)> = lines.iter()
+        .enumerate()
+        .filter_map(|(idx, line)| crate_of(line).map(|name| (idx, name)))
+        .collect();
+    if entries.iter().any(|&(_, name)| name == new_crate) {
+        return Some(text.to_string());
+    }
+    let idx = match entries.iter().rev().find(|&&(_, name)| name < new_crate) {
+        Some(&(idx, _)) => idx + 1,
+        None => entries.first()?.0,
+    };
+    lines.insert(idx, line);
+    Some(lines.join("\n") + "\n")
+}
+
+/// A day's crate name, if it's all that `text` holds once `prefix` and
+/// `suffix` are removed.
+fn day_crate<'a>(text: &'a str, prefix: &str, suffix: &str) -> Option<&'a str> {
+    let name = text.strip_prefix(prefix)?.split(suffix).next()?;
+    let digits = name.strip_prefix('c')?;
+    if digits.len() == 2 && digits.chars().all(|c| c.is_ascii_digit()) { Some(name) } else { None }
+}
+
+fn workspace_member(line: &str) -> Option<&str> {
+    day_crate(line.trim(), "\"", "\",")
+}
+
+fn dependency(line: &str) -> Option<&str> {
+    day_crate(line, "", " = ")
+}
+
+fn days_entry(line: &str) -> Option<&str> {
+    day_crate(line.trim(), "&", "::Day,")
+}
+
+fn read(path: &Path) -> Result<String, Error> {
+    fs::read_to_string(path).map_err(|err| Error::Io(Some(path.display().to_string()), err))
+}
+
+fn write(path: &Path, text: &str) -> Result<(), Error> {
+    fs::write(path, text).map_err(|err| Error::Write(path.display().to_string(), err))
+}
+
+/// Add `line` to the day registrations in the file at `path`.
+fn register_in(path: &Path, line: &str, crate_of: fn(&str) -> Option<&str>) -> Result<(), Error> {
+    let text = read(path)?;
+    match register(&text, line, crate_of) {
+        Some(updated) => write(path, &updated),
+        None => {
+            let err = io::Error::new(io::ErrorKind::InvalidData, "couldn't find where days are registered");
+            Err(Error::Write(path.display().to_string(), err))
+        },
+    }
+}
+
+/// Create a crate for a new day in the workspace at `root` and register it
+/// with the workspace and the runner. Returns the files that were created.
+pub fn scaffold(root: &Path, day: u32, name: &str) -> Result<Vec<PathBuf>, Error> {
+    let name_of_crate = crate_name(day);
+    let dir = root.join(&name_of_crate);
+    if dir.exists() {
+        let err = io::Error::new(io::ErrorKind::AlreadyExists, "it already exists");
+        return Err(Error::Write(dir.display().to_string(), err));
+    }
+    let src = dir.join("src");
+    fs::create_dir_all(&src).map_err(|err| Error::Write(src.display().to_string(), err))?;
+    let files = [
+        (dir.join("Cargo.toml"), render(CARGO_TOML, day, name)),
+        (src.join("main.rs"), render(MAIN_RS, day, name)),
+        (src.join("lib.rs"), render(LIB_RS, day, name)),
+        (src.join("gen.rs"), render(GEN_RS, day, name)),
+        // paste the puzzle's worked example here
+        (src.join("example.txt"), String::new()),
+    ];
+    for (path, text) in &files {
+        write(path, text)?;
+    }
+
+    register_in(&root.join("Cargo.toml"), &format!("    \"{}\",", name_of_crate), workspace_member)?;
+    let dependency_line = format!("{0} = {{ path = \"../{0}\" }}", name_of_crate);
+    register_in(&root.join("aoc").join("Cargo.toml"), &dependency_line, dependency)?;
+    register_in(&root.join("aoc").join("src").join("days.rs"), &format!("        &{}::Day,", name_of_crate), days_entry)?;
+    Ok(files.into_iter().map(|(path, _)| path).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn registers_in_order() {
+        let members = "members = [\n    \"common\",\n    \"c01\",\n    \"c03\",\n]\n";
+        assert_eq!(register(members, "    \"c02\",", workspace_member).unwrap(),
+                   "members = [\n    \"common\",\n    \"c01\",\n    \"c02\",\n    \"c03\",\n]\n");
+        assert_eq!(register(members, "    \"c01\",", workspace_member).unwrap(), members);
+        let days = "    vec![\n        &c10::Day,\n    ]\n";
+        assert_eq!(register(days, "        &c11::Day,", days_entry).unwrap(),
+                   "    vec![\n        &c10::Day,\n        &c11::Day,\n    ]\n");
+        assert_eq!(register("[dependencies]\n", "c11 = { path = \"../c11\" }", dependency), None);
+    }
+
+    #[test]
+    fn scaffolds_a_day() {
+        let root = env::temp_dir().join(format!("aoc-new-test-{}", std::process::id()));
+        fs::create_dir_all(root.join("aoc").join("src")).unwrap();
+        fs::write(root.join("Cargo.toml"), "[workspace]\nmembers = [\n    \"aoc\",\n    \"c10\",\n]\n").unwrap();
+        fs::write(root.join("aoc").join("Cargo.toml"), "[dependencies]\nc10 = { path = \"../c10\" }\n").unwrap();
+        fs::write(root.join("aoc").join("src").join("days.rs"), "vec![\n    &c10::Day,\n]\n").unwrap();
+
+        let files = scaffold(&root, 11, "Dumbo \"Octopus\"").unwrap();
+        assert_eq!(files.len(), 5);
+        let lib = fs::read_to_string(root.join("c11").join("src").join("lib.rs")).unwrap();
+        assert!(lib.contains("const DAY: u32 = 11;"));
+        assert!(lib.contains(r#"const NAME: &'static str = "Dumbo \"Octopus\"";"#));
+        assert!(lib.contains("https://adventofcode.com/2021/day/11\n"));
+        assert!(fs::read_to_string(root.join("Cargo.toml")).unwrap().ends_with("    \"c10\",\n    \"c11\",\n]\n"));
+        assert!(fs::read_to_string(root.join("aoc").join("src").join("days.rs")).unwrap().contains("&c11::Day,"));
+        assert!(scaffold(&root, 11, "Again").is_err());
+        fs::remove_dir_all(root).unwrap();
+    }
+}
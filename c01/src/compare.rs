@@ -0,0 +1,154 @@
+use std::collections::VecDeque;
+use std::fmt;
+use std::str::FromStr;
+
+/// Which way a measurement (or window sum) has to change from an earlier one
+/// to be counted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Direction {
+    #[default]
+    Deeper,
+    Shallower,
+    /// Neither deeper nor shallower, i.e. a plateau.
+    Same,
+}
+
+impl fmt::Display for Direction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.pad(match self {
+            Direction::Deeper => "deeper",
+            Direction::Shallower => "shallower",
+            Direction::Same => "same",
+        })
+    }
+}
+
+impl FromStr for Direction {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Direction, ()> {
+        match s {
+            "deeper" => Ok(Direction::Deeper),
+            "shallower" => Ok(Direction::Shallower),
+            "same" => Ok(Direction::Same),
+            _ => Err(()),
+        }
+    }
+}
+
+/// What to count when comparing each value with an earlier one. The default
+/// is the puzzle's: values deeper than the one just before.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Comparison {
+    pub direction: Direction,
+    /// Deeper and shallower values only count if they changed by more than
+    /// this, while a change of up to this much counts as the same.
+    pub min_delta: u64,
+    /// How many values back the earlier value is, at least 1.
+    pub lag: usize,
+}
+
+impl Default for Comparison {
+    fn default() -> Comparison {
+        Comparison {
+            direction: Direction::Deeper,
+            min_delta: 0,
+            lag: 1,
+        }
+    }
+}
+
+impl Comparison {
+    /// Whether `later` counts when compared with `earlier`.
+    pub fn matches(&self, earlier: i64, later: i64) -> bool {
+        let change = later.abs_diff(earlier);
+        match self.direction {
+            Direction::Deeper => later > earlier && change > self.min_delta,
+            Direction::Shallower => later < earlier && change > self.min_delta,
+            Direction::Same => change <= self.min_delta,
+        }
+    }
+}
+
+/// Counts the values that match a `Comparison` with the value `lag` before
+/// them, one value at a time, keeping only the last `lag` values.
+pub struct Counter {
+    comparison: Comparison,
+    earlier: VecDeque<i64>,
+    pub count: usize,
+}
+
+impl Counter {
+    /// Panics if the comparison's lag is zero.
+    pub fn new(comparison: Comparison) -> Counter {
+        assert!(comparison.lag > 0, "a value must be compared with an earlier one");
+        Counter {
+            comparison,
+            earlier: VecDeque::with_capacity(comparison.lag + 1),
+            count: 0,
+        }
+    }
+
+    /// Add the next value, returning the value it was compared with if it
+    /// matched.
+    pub fn push(&mut self, value: i64) -> Option<i64> {
+        self.earlier.push_back(value);
+        if self.earlier.len() <= self.comparison.lag {
+            return None;
+        }
+        let earlier = self.earlier.pop_front().unwrap();
+        if self.comparison.matches(earlier, value) {
+            self.count += 1;
+            Some(earlier)
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn count(comparison: Comparison, values: &[i64]) -> usize {
+        let mut counter = Counter::new(comparison);
+        for &value in values {
+            counter.push(value);
+        }
+        counter.count
+    }
+
+    #[test]
+    fn counts_each_direction() {
+        let values = [10, 12, 12, 5, 6, 20];
+        assert_eq!(count(Comparison::default(), &values), 3);
+        let shallower = Comparison {
+            direction: Direction::Shallower,
+            ..Comparison::default()
+        };
+        assert_eq!(count(shallower, &values), 1);
+        let same = Comparison {
+            direction: Direction::Same,
+            ..Comparison::default()
+        };
+        assert_eq!(count(same, &values), 1);
+        assert_eq!(count(Comparison { min_delta: 1, ..same }, &values), 2);
+    }
+
+    #[test]
+    fn thresholds_and_lags() {
+        let values = [10, 12, 12, 5, 6, 20];
+        let big_changes = Comparison {
+            min_delta: 2,
+            ..Comparison::default()
+        };
+        assert_eq!(count(big_changes, &values), 1);
+        let two_back = Comparison {
+            lag: 2,
+            ..Comparison::default()
+        };
+        // 12 > 10, but 5 and 6 aren't deeper than 12, then 20 > 5
+        assert_eq!(count(two_back, &values), 2);
+        assert_eq!(count(Comparison { lag: 6, ..two_back }, &values), 0);
+    }
+}
@@ -0,0 +1,154 @@
+use std::str::FromStr;
+
+use aoc_common::info;
+
+/// How a measurement is judged against the rolling median of its neighbours.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Method {
+    /// Flag it if it's further from the median than the threshold.
+    Median,
+    /// Flag it if it's further from the median than the threshold times the
+    /// median absolute deviation (MAD) of the neighbourhood, so noisier
+    /// stretches are allowed bigger swings.
+    Mad,
+}
+
+impl FromStr for Method {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Method, ()> {
+        match s {
+            "median" => Ok(Method::Median),
+            "mad" => Ok(Method::Mad),
+            _ => Err(()),
+        }
+    }
+}
+
+impl Method {
+    /// The threshold to use if none is given.
+    pub fn default_threshold(&self) -> u64 {
+        match self {
+            Method::Median => 50,
+            Method::Mad => 10,
+        }
+    }
+}
+
+/// A rolling median filter for glitches in depth measurements, looking at
+/// `radius` measurements either side of each one (fewer at the ends).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Filter {
+    pub method: Method,
+    pub radius: usize,
+    pub threshold: u64,
+}
+
+/// A measurement that was flagged as a glitch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Glitch {
+    pub line: usize,
+    pub depth: i32,
+    /// The median of its neighbourhood, which replaces it.
+    pub median: i32,
+}
+
+/// Depths with their glitches replaced, and the glitches that were found.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Filtered {
+    pub depths: Vec<i32>,
+    pub glitches: Vec<Glitch>,
+}
+
+impl Filter {
+    /// Flag each of `depths`, as read by `load_depths`, that's too far from
+    /// the median of its neighbourhood, and replace it with that median. The
+    /// neighbourhood includes the measurement itself and is always judged on
+    /// the raw depths, so one glitch doesn't hide or cause another.
+    pub fn apply(&self, depths: &[i32]) -> Filtered {
+        let mut filtered = Filtered {
+            depths: depths.to_vec(),
+            glitches: Vec::new(),
+        };
+        for (idx, &depth) in depths.iter().enumerate() {
+            let neighbourhood = &depths[idx.saturating_sub(self.radius)..depths.len().min(idx + self.radius + 1)];
+            let middle = median(neighbourhood.iter().map(|&depth| i64::from(depth)).collect());
+            let deviation = i64::from(depth).abs_diff(middle);
+            let limit = match self.method {
+                Method::Median => self.threshold,
+                Method::Mad => {
+                    let mad = median(neighbourhood.iter().map(|&depth| i64::from(depth).abs_diff(middle) as i64).collect());
+                    // A flat neighbourhood has no deviation at all, so allow
+                    // at least a change of one per threshold.
+                    self.threshold.saturating_mul((mad as u64).max(1))
+                },
+            };
+            if deviation > limit {
+                // The median is always one of the depths
+                let median = middle as i32;
+                info!("line {}: {} is {} from the median {}, flagged as a glitch", idx + 1, depth, deviation, median);
+                filtered.depths[idx] = median;
+                filtered.glitches.push(Glitch {
+                    line: idx + 1,
+                    depth,
+                    median,
+                });
+            }
+        }
+        filtered
+    }
+}
+
+/// The middle of `values`, or the upper of the two middle ones if there's an
+/// even number of them, so it's always one of the values. Panics if there are
+/// none.
+fn median(mut values: Vec<i64>) -> i64 {
+    let middle = values.len() / 2;
+    *values.select_nth_unstable(middle).1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{load_depths, single_measurement};
+
+    #[test]
+    fn replaces_a_spike() {
+        let mut depths = load_depths(include_str!("example.txt").as_bytes()).unwrap();
+        depths[4] = 900;
+        assert_eq!(single_measurement(&depths), 7);
+        for method in [Method::Median, Method::Mad] {
+            let filter = Filter {
+                method,
+                radius: 2,
+                threshold: method.default_threshold(),
+            };
+            let filtered = filter.apply(&depths);
+            assert_eq!(filtered.glitches, vec![Glitch { line: 5, depth: 900, median: 210 }]);
+            assert_eq!(filtered.depths[4], 210);
+            assert_eq!(single_measurement(&filtered.depths), 6);
+        }
+    }
+
+    #[test]
+    fn thresholds() {
+        let depths = [100, 101, 100, 130, 101, 100, 102];
+        let filter = Filter {
+            method: Method::Median,
+            radius: 3,
+            threshold: 29,
+        };
+        assert!(filter.apply(&depths).glitches.is_empty());
+        assert_eq!(Filter { threshold: 28, ..filter }.apply(&depths).glitches.len(), 1);
+        let mad = Filter {
+            method: Method::Mad,
+            ..filter
+        };
+        // The MAD around line 4 is 1, so only something more than 29 away
+        // from the median would be flagged
+        assert!(mad.apply(&depths).glitches.is_empty());
+        assert_eq!(mad.apply(&[5, 5, 5, 9, 5]).glitches.len(), 0);
+        assert_eq!(Filter { threshold: 3, ..mad }.apply(&[5, 5, 5, 9, 5]).glitches.len(), 1);
+        assert!(mad.apply(&[]).glitches.is_empty());
+    }
+}
@@ -0,0 +1,24 @@
+use aoc_common::Rng;
+
+pub const USAGE: &str = " [OPTIONS]
+
+Generate a random series of depth measurements, one per line. SIZE is the
+number of measurements (2000 by default).
+
+OPTIONS:
+ --max-step STEP: Largest change between neighbouring measurements (default 10)
+ -h: Print this usage message and exit
+";
+
+/// Generate `count` depths as a random walk that changes by at most
+/// `max_step` each time and never rises above the surface.
+pub fn depths(rng: &mut Rng, count: usize, max_step: u32) -> String {
+    let max_step = i64::from(max_step);
+    let mut depth = rng.range(100, 200);
+    let mut text = String::new();
+    for _ in 0..count {
+        text += &format!("{}\n", depth);
+        depth = (depth + rng.range(-max_step, max_step)).max(0);
+    }
+    text
+}
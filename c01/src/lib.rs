@@ -0,0 +1,360 @@
+use std::io::BufRead;
+
+use aoc_common::solver::Parts;
+use aoc_common::log::{self, Level};
+use aoc_common::{debug, error, info, input, solver, Answer, ArgError, Args, Error, Puzzle, Rng};
+
+use compare::{Comparison, Counter};
+use filter::{Filter, Glitch};
+use stats::Stats;
+use stream::Analyzer;
+use window::RollingSumsExt;
+
+pub mod compare;
+pub mod filter;
+pub mod gen;
+pub mod stats;
+pub mod stream;
+pub mod window;
+
+const USAGE: &str = " [OPTIONS] FILENAME
+
+Analyze sonar measurements.
+
+The file given by FILENAME contains a series of depth measurements separated by
+newlines. This program will count how many measurements are deeper than their
+predecessor, both individually and in a window of three measurements. The
+options below can count other changes instead, using the same comparison for
+both counts.
+
+OPTIONS:
+ --window SIZE: Use windows of SIZE measurements instead of three for the
+                windowed count
+ --count deeper|shallower|same: Count measurements (or windows) that are
+                                deeper, shallower or the same as the earlier
+                                one (default deeper)
+ --min-delta DELTA: Only count deeper or shallower measurements that changed
+                    by more than DELTA, and count changes of up to DELTA as
+                    the same (default 0)
+ --lag K: Compare each measurement (or window) with the one K steps before it
+          instead of the one just before (default 1)
+ --filter median|mad: Before counting, flag glitches that are too far from
+                      the median of their neighbours, report their line
+                      numbers and count with the median in their place. With
+                      median, too far is more than the threshold; with mad,
+                      it's more than the threshold times the median absolute
+                      deviation of the neighbours
+ --filter-radius R: Look at R measurements either side for --filter (default
+                    3)
+ --threshold T: How far from the median a glitch is for --filter (default 50
+                for median and 10 for mad)
+ --raw: Report the glitches found by --filter, but count on the raw depths
+ --stream: Count while reading FILENAME a line at a time, keeping only the
+           latest window of measurements, for inputs too large to hold
+ --progress N: Stream, and log the counts so far at the info level after
+               every N measurements. They're shown unless --log or --trace
+               asks for a different level
+ --stats: After the counts, report the number of measurements, their range,
+          mean and median, the longest runs getting deeper and shallower, the
+          largest change between neighbours, and how many measurements are
+          deeper, shallower or the same as the last
+ -h: Print this usage message and exit
+
+Use - as FILENAME to read from standard input.
+
+https://adventofcode.com/2021/day/1
+";
+
+pub struct Day;
+
+impl Puzzle for Day {
+    const DAY: u32 = 1;
+    const NAME: &'static str = "Sonar Sweep";
+    const USAGE: &'static str = USAGE;
+    const FLAGS: &'static [&'static str] = &["--raw", "--stats", "--stream"];
+    const OPTIONS: &'static [&'static str] = &["--window", "--count", "--min-delta", "--lag", "--filter",
+                                               "--filter-radius", "--threshold", "--progress"];
+    const GEN_USAGE: &'static str = gen::USAGE;
+    const GEN_OPTIONS: &'static [&'static str] = &["--max-step"];
+    const ANSWER_OPTIONS: &'static [&'static str] = &["--window", "--count", "--min-delta", "--lag", "--filter",
+                                                      "--filter-radius", "--threshold", "--raw"];
+
+    type Input = Sonar;
+
+    fn parse(&self, text: &str, args: &Args) -> Result<Sonar, Error> {
+        let depths = load_depths(text.as_bytes())?;
+        let (depths, glitches) = match glitch_filter(args)? {
+            Some(filter) => {
+                let filtered = filter.apply(&depths);
+                (if args.has_flag("--raw") { depths } else { filtered.depths }, Some(filtered.glitches))
+            },
+            None => (depths, None),
+        };
+        Ok(Sonar {
+            depths,
+            window: window_size(args)?,
+            comparison: comparison(args)?,
+            glitches,
+        })
+    }
+
+    fn part1(&self, sonar: &Sonar) -> Answer {
+        single_answer(count_changes(&sonar.depths, 1, &sonar.comparison), &sonar.comparison)
+    }
+
+    fn part2(&self, sonar: &Sonar) -> Answer {
+        let count = count_changes(&sonar.depths, sonar.window, &sonar.comparison);
+        window_answer(count, sonar.window, &sonar.comparison)
+    }
+
+    fn report(&self, sonar: &Sonar, args: &Args) -> Vec<Answer> {
+        let mut report = Vec::new();
+        if let Some(glitches) = &sonar.glitches {
+            report.push(glitch_report(glitches, args.has_flag("--raw")));
+        }
+        if let Some(stats) = Stats::new(&sonar.depths).filter(|_| args.has_flag("--stats")) {
+            report.extend(stats_report(&stats));
+        }
+        report
+    }
+
+    fn streams(&self, args: &Args) -> bool {
+        args.has_flag("--stream") || args.value("--progress").is_some()
+    }
+
+    fn solve_stream(&self, reader: &mut dyn BufRead, args: &Args, parts: Parts) -> Result<Vec<(u32, Answer)>, Error> {
+        if let Some(option) = ["--stats", "--filter"].into_iter().find(|&option| args.has_flag(option)
+                                                                       || args.value(option).is_some()) {
+            return Err(ArgError::NotWithStream(option.to_string()).into());
+        }
+        let progress_every = args.parsed_value_in("--progress", 1..)?;
+        // asking for progress is asking to see it, unless the level was chosen
+        if progress_every.is_some() && args.value("--log").is_none() && !args.has_flag("--trace") {
+            log::set_max_level(Level::Info);
+        }
+        let comparison = comparison(args)?;
+        let mut analyzer = Analyzer::new(window_size(args)?, comparison);
+        analyzer.read(reader, progress_every, |analyzer| {
+            info!("{} measurements: {} counted, {} windows counted", analyzer.samples, analyzer.counted,
+                  analyzer.windows_counted);
+        })?;
+        let answers = [
+            (1, single_answer(analyzer.counted, &comparison)),
+            (2, window_answer(analyzer.windows_counted, analyzer.window_size(), &comparison)),
+        ];
+        Ok(answers.into_iter()
+            .filter(|&(part, _)| parts.contains(part))
+            .collect())
+    }
+
+    fn generate(&self, rng: &mut Rng, args: &Args) -> Result<String, Error> {
+        let count = solver::gen_size(args, 2000)?;
+        let max_step = args.parsed_value("--max-step")?.unwrap_or(10);
+        Ok(gen::depths(rng, count, max_step))
+    }
+}
+
+/// The puzzle's window size for the second count.
+pub const DEFAULT_WINDOW: usize = 3;
+
+/// Depth measurements, along with how to analyze them.
+pub struct Sonar {
+    /// The depths to count on, which are filtered unless `--raw` was given.
+    pub depths: Vec<i32>,
+    /// Number of measurements in each window for the windowed count.
+    pub window: usize,
+    /// What both counts look for.
+    pub comparison: Comparison,
+    /// The glitches flagged by `--filter`, or `None` without it.
+    pub glitches: Option<Vec<Glitch>>,
+}
+
+fn window_size(args: &Args) -> Result<usize, Error> {
+    Ok(args.parsed_value_in("--window", 1..)?.unwrap_or(DEFAULT_WINDOW))
+}
+
+fn comparison(args: &Args) -> Result<Comparison, Error> {
+    let default = Comparison::default();
+    Ok(Comparison {
+        direction: args.parsed_value("--count")?.unwrap_or(default.direction),
+        min_delta: args.parsed_value("--min-delta")?.unwrap_or(default.min_delta),
+        lag: args.parsed_value_in("--lag", 1..)?.unwrap_or(default.lag),
+    })
+}
+
+fn glitch_filter(args: &Args) -> Result<Option<Filter>, Error> {
+    let Some(method) = args.parsed_value::<filter::Method>("--filter")? else {
+        return Ok(None);
+    };
+    Ok(Some(Filter {
+        method,
+        radius: args.parsed_value_in("--filter-radius", 1..)?.unwrap_or(3),
+        threshold: args.parsed_value("--threshold")?.unwrap_or(method.default_threshold()),
+    }))
+}
+
+fn single_answer(count: usize, comparison: &Comparison) -> Answer {
+    with_comparison(Answer::new("Single-measurement count", count), comparison)
+}
+
+fn window_answer(count: usize, window: usize, comparison: &Comparison) -> Answer {
+    with_comparison(Answer::new("Windowed-measurement count", count).with("window", window), comparison)
+}
+
+/// Note what was counted on an answer, unless it's the puzzle's comparison.
+fn with_comparison(answer: Answer, comparison: &Comparison) -> Answer {
+    if *comparison == Comparison::default() {
+        return answer;
+    }
+    answer.with("count", comparison.direction.to_string())
+        .with("min_delta", comparison.min_delta)
+        .with("lag", comparison.lag)
+}
+
+fn glitch_report(glitches: &[Glitch], raw: bool) -> Answer {
+    let lines: Vec<usize> = glitches.iter().map(|glitch| glitch.line).collect();
+    Answer::new("Glitches flagged", glitches.len())
+        .with("lines", lines)
+        .with("counted_on", if raw { "raw" } else { "filtered" })
+}
+
+fn stats_report(stats: &Stats) -> Vec<Answer> {
+    let run = |label, run: stats::Run| {
+        Answer::new(label, run.length()).with("first_line", run.first_line).with("last_line", run.last_line)
+    };
+    let mut report = vec![
+        Answer::new("Measurements", stats.count),
+        Answer::new("Shallowest depth", stats.min),
+        Answer::new("Deepest depth", stats.max),
+        Answer::new("Mean depth", (stats.mean * 100.0).round() / 100.0),
+        Answer::new("Median depth", stats.median),
+        run("Longest run getting deeper", stats.longest_deeper_run),
+        run("Longest run getting shallower", stats.longest_shallower_run),
+    ];
+    if let Some(jump) = stats.largest_jump {
+        report.push(Answer::new("Largest change", jump.change())
+            .with("line", jump.line)
+            .with("from", jump.from)
+            .with("to", jump.to));
+    }
+    report.push(Answer::new("Deeper than the last", stats.deeper));
+    report.push(Answer::new("Shallower than the last", stats.shallower));
+    report.push(Answer::new("Same as the last", stats.unchanged));
+    report
+}
+
+/// Count how many measurements are deeper than their predecessor.
+pub fn single_measurement(depths: &[i32]) -> usize {
+    window_measurement(depths, 1)
+}
+
+/// Count how many windows of `size` measurements have a larger sum than the
+/// window before them. There are no windows if there are fewer than `size`
+/// measurements. Panics if `size` is zero.
+pub fn window_measurement(depths: &[i32], size: usize) -> usize {
+    count_changes(depths, size, &Comparison::default())
+}
+
+/// Count how many windows of `size` measurements have a sum that matches
+/// `comparison` with the sum of an earlier window. Windows of one are single
+/// measurements. Panics if `size` or the comparison's lag is zero.
+pub fn count_changes(depths: &[i32], size: usize, comparison: &Comparison) -> usize {
+    let mut counter = Counter::new(*comparison);
+    for (idx, sum) in depths.iter().map(|&depth| i64::from(depth)).rolling_sums(size).enumerate() {
+        if let Some(earlier) = counter.push(sum) {
+            let earlier_idx = idx - comparison.lag;
+            if size == 1 {
+                debug!("line {}: {} against {} on line {} counts as {} (count {})", idx + 1, sum, earlier,
+                       earlier_idx + 1, comparison.direction, counter.count);
+            } else {
+                debug!("lines {}-{}: sum {} against {} for lines {}-{} counts as {} (count {})", idx + 1, idx + size,
+                       sum, earlier, earlier_idx + 1, earlier_idx + size, comparison.direction, counter.count);
+            }
+        }
+    }
+    counter.count
+}
+
+/// Read depth measurements, one per line. Pass `text.as_bytes()` to read
+/// from a string.
+pub fn load_depths<R: BufRead>(reader: R) -> Result<Vec<i32>, Error> {
+    let mut depths = Vec::new();
+    for (idx, line) in input::lines(reader)?.iter().enumerate() {
+        let depth = error::parse_number(idx + 1, line, line, "a depth")?;
+        depths.push(depth);
+    }
+    Ok(depths)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = include_str!("example.txt");
+
+    #[test]
+    fn single_measurement_counts_increases() {
+        let depths = load_depths(EXAMPLE.as_bytes()).unwrap();
+        assert_eq!(single_measurement(&depths), 7);
+    }
+
+    #[test]
+    fn window_measurement_counts_window_increases() {
+        let depths = load_depths(EXAMPLE.as_bytes()).unwrap();
+        assert_eq!(window_measurement(&depths, 3), 5);
+        assert_eq!(window_measurement(&depths, 1), single_measurement(&depths));
+        assert_eq!(window_measurement(&depths, 9), 1);
+        assert_eq!(window_measurement(&depths, 10), 0);
+    }
+
+    #[test]
+    fn short_inputs_have_no_increases() {
+        assert_eq!(single_measurement(&[]), 0);
+        assert_eq!(single_measurement(&[5]), 0);
+        assert_eq!(window_measurement(&[5, 6], 3), 0);
+        assert_eq!(window_measurement(&[5, 6, 7], 3), 0);
+        assert_eq!(window_measurement(&[1, i32::MAX, i32::MAX, i32::MAX], 3), 1);
+    }
+
+    #[test]
+    fn count_changes_with_other_comparisons() {
+        let depths = load_depths(EXAMPLE.as_bytes()).unwrap();
+        let shallower = Comparison {
+            direction: compare::Direction::Shallower,
+            ..Comparison::default()
+        };
+        assert_eq!(count_changes(&depths, 1, &shallower), 2);
+        assert_eq!(count_changes(&depths, 3, &shallower), 1);
+        let big_increases = Comparison {
+            min_delta: 10,
+            ..Comparison::default()
+        };
+        assert_eq!(count_changes(&depths, 1, &big_increases), 2);
+        // Comparing each window of three with the one three steps back never
+        // shares a measurement between them.
+        let three_back = Comparison {
+            lag: 3,
+            ..Comparison::default()
+        };
+        assert_eq!(count_changes(&depths, 3, &three_back), 5);
+    }
+
+    #[test]
+    fn load_depths_reports_bad_line() {
+        match load_depths("199\n2x0\n".as_bytes()) {
+            Err(Error::Parse(err)) => {
+                assert_eq!((err.line, err.column), (2, 1));
+                assert_eq!(err.text, "2x0");
+            },
+            other => panic!("expected a parse error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn generated_depths_parse() {
+        let text = gen::depths(&mut Rng::new(1), 100, 10);
+        let depths = load_depths(text.as_bytes()).unwrap();
+        assert_eq!(depths.len(), 100);
+        assert!(depths.windows(2).all(|pair| (pair[1] - pair[0]).abs() <= 10));
+    }
+}
@@ -0,0 +1,166 @@
+use std::cmp::Ordering;
+
+/// A run of consecutive measurements, given by the line numbers of the first
+/// and last of them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Run {
+    pub first_line: usize,
+    pub last_line: usize,
+}
+
+impl Run {
+    /// The number of measurements in the run.
+    pub fn length(&self) -> usize {
+        self.last_line - self.first_line + 1
+    }
+}
+
+/// A change in depth from one measurement to the next, at the line of the
+/// second measurement.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Jump {
+    pub line: usize,
+    pub from: i32,
+    pub to: i32,
+}
+
+impl Jump {
+    /// How much deeper the second measurement is, negative if it's
+    /// shallower.
+    pub fn change(&self) -> i64 {
+        i64::from(self.to) - i64::from(self.from)
+    }
+}
+
+/// Statistics about a series of depth measurements.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Stats {
+    pub count: usize,
+    pub min: i32,
+    pub max: i32,
+    pub mean: f64,
+    /// The middle depth, or the mean of the two middle depths if there's an
+    /// even number of them.
+    pub median: f64,
+    /// The longest run where each measurement is deeper than the last. The
+    /// earliest is picked if there's a tie, and it's a single measurement if
+    /// none is deeper than the last.
+    pub longest_deeper_run: Run,
+    /// Like `longest_deeper_run`, but for measurements that get shallower.
+    pub longest_shallower_run: Run,
+    /// The largest change either way between neighbouring measurements, the
+    /// earliest if there's a tie, or `None` if there's only one measurement.
+    pub largest_jump: Option<Jump>,
+    /// Number of measurements deeper than the last.
+    pub deeper: usize,
+    /// Number of measurements shallower than the last.
+    pub shallower: usize,
+    /// Number of measurements the same as the last.
+    pub unchanged: usize,
+}
+
+impl Stats {
+    /// Statistics about `depths`, as read by `load_depths`, or `None` if
+    /// there are none.
+    pub fn new(depths: &[i32]) -> Option<Stats> {
+        let count = depths.len();
+        let mut sorted = depths.to_vec();
+        sorted.sort_unstable();
+        let (&min, &max) = (sorted.first()?, sorted.last()?);
+        let median = if count % 2 == 1 {
+            f64::from(sorted[count / 2])
+        } else {
+            (f64::from(sorted[count / 2 - 1]) + f64::from(sorted[count / 2])) / 2.0
+        };
+        let sum: i64 = depths.iter().map(|&depth| i64::from(depth)).sum();
+        let first = Run {
+            first_line: 1,
+            last_line: 1,
+        };
+
+        let mut stats = Stats {
+            count,
+            min,
+            max,
+            mean: sum as f64 / count as f64,
+            median,
+            longest_deeper_run: first,
+            longest_shallower_run: first,
+            largest_jump: None,
+            deeper: 0,
+            shallower: 0,
+            unchanged: 0,
+        };
+        let (mut deeper_run, mut shallower_run) = (first, first);
+        for (idx, pair) in depths.windows(2).enumerate() {
+            let line = idx + 2;
+            let jump = Jump {
+                line,
+                from: pair[0],
+                to: pair[1],
+            };
+            if stats.largest_jump.is_none_or(|largest| jump.change().abs() > largest.change().abs()) {
+                stats.largest_jump = Some(jump);
+            }
+            deeper_run = extend_run(deeper_run, line, pair[1] > pair[0]);
+            shallower_run = extend_run(shallower_run, line, pair[1] < pair[0]);
+            if deeper_run.length() > stats.longest_deeper_run.length() {
+                stats.longest_deeper_run = deeper_run;
+            }
+            if shallower_run.length() > stats.longest_shallower_run.length() {
+                stats.longest_shallower_run = shallower_run;
+            }
+            match pair[1].cmp(&pair[0]) {
+                Ordering::Greater => stats.deeper += 1,
+                Ordering::Less => stats.shallower += 1,
+                Ordering::Equal => stats.unchanged += 1,
+            }
+        }
+        Some(stats)
+    }
+}
+
+/// The run ending at `line`: `run` continued if `continues`, or a new run of
+/// just that measurement otherwise.
+fn extend_run(run: Run, line: usize, continues: bool) -> Run {
+    if continues {
+        Run {
+            last_line: line,
+            ..run
+        }
+    } else {
+        Run {
+            first_line: line,
+            last_line: line,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::load_depths;
+
+    #[test]
+    fn example_stats() {
+        let depths = load_depths(include_str!("example.txt").as_bytes()).unwrap();
+        let stats = Stats::new(&depths).unwrap();
+        assert_eq!((stats.count, stats.min, stats.max), (10, 199, 269));
+        assert_eq!((stats.mean, stats.median), (225.6, 209.0));
+        assert_eq!(stats.longest_deeper_run, Run { first_line: 1, last_line: 4 });
+        assert_eq!(stats.longest_shallower_run, Run { first_line: 4, last_line: 5 });
+        assert_eq!(stats.largest_jump, Some(Jump { line: 7, from: 207, to: 240 }));
+        assert_eq!((stats.deeper, stats.shallower, stats.unchanged), (7, 2, 0));
+    }
+
+    #[test]
+    fn counts_unchanged_depths() {
+        let stats = Stats::new(&[5, 5, 3, 3, 3, 9]).unwrap();
+        assert_eq!((stats.deeper, stats.shallower, stats.unchanged), (1, 1, 3));
+        assert_eq!(stats.median, 4.0);
+        assert_eq!(stats.largest_jump.unwrap().change(), 6);
+        assert_eq!(stats.longest_shallower_run.length(), 2);
+        assert_eq!(Stats::new(&[7]).unwrap().largest_jump, None);
+        assert_eq!(Stats::new(&[]), None);
+    }
+}
@@ -0,0 +1,110 @@
+use std::io::BufRead;
+
+use aoc_common::{error, Error};
+
+use crate::compare::{Comparison, Counter};
+use crate::window::Window;
+
+/// Running counts over a stream of depths, both single and windowed, of
+/// those that match a `Comparison`. Only the latest window of depths (and the
+/// comparison's lag of earlier ones) is kept, so there's no limit to how much
+/// can be analyzed.
+pub struct Analyzer {
+    window: Window<i64>,
+    depths: Counter,
+    sums: Counter,
+    /// Number of depths seen so far.
+    pub samples: usize,
+    /// Depths that matched the comparison.
+    pub counted: usize,
+    /// Windows whose sums matched the comparison.
+    pub windows_counted: usize,
+}
+
+impl Analyzer {
+    /// An analyzer with windows of `window` depths. Panics if `window` or
+    /// the comparison's lag is zero.
+    pub fn new(window: usize, comparison: Comparison) -> Analyzer {
+        Analyzer {
+            window: Window::new(window),
+            depths: Counter::new(comparison),
+            sums: Counter::new(comparison),
+            samples: 0,
+            counted: 0,
+            windows_counted: 0,
+        }
+    }
+
+    pub fn window_size(&self) -> usize {
+        self.window.size()
+    }
+
+    pub fn push(&mut self, depth: i32) {
+        let depth = i64::from(depth);
+        self.samples += 1;
+        self.depths.push(depth);
+        self.counted = self.depths.count;
+        if let Some(sum) = self.window.push(depth) {
+            self.sums.push(sum);
+            self.windows_counted = self.sums.count;
+        }
+    }
+
+    /// Read depths one line at a time and add each of them. If
+    /// `progress_every` is given, `progress` is called after every that many
+    /// depths.
+    pub fn read<R, F>(&mut self, reader: R, progress_every: Option<usize>, mut progress: F) -> Result<(), Error>
+        where R: BufRead, F: FnMut(&Analyzer)
+    {
+        for line in reader.lines() {
+            let line = line.map_err(|err| Error::Io(None, err))?;
+            self.push(error::parse_number(self.samples + 1, &line, &line, "a depth")?);
+            if progress_every.is_some_and(|every| self.samples.is_multiple_of(every)) {
+                progress(self);
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compare::Direction;
+    use crate::{count_changes, gen, load_depths, single_measurement, window_measurement};
+    use aoc_common::Rng;
+
+    #[test]
+    fn matches_whole_file_counts() {
+        let text = gen::depths(&mut Rng::new(4), 500, 10);
+        let depths = load_depths(text.as_bytes()).unwrap();
+        for window in [1, 3, 7] {
+            let mut analyzer = Analyzer::new(window, Comparison::default());
+            analyzer.read(text.as_bytes(), None, |_| ()).unwrap();
+            assert_eq!(analyzer.samples, 500);
+            assert_eq!(analyzer.counted, single_measurement(&depths));
+            assert_eq!(analyzer.windows_counted, window_measurement(&depths, window));
+        }
+        let comparison = Comparison {
+            direction: Direction::Same,
+            min_delta: 2,
+            lag: 4,
+        };
+        let mut analyzer = Analyzer::new(3, comparison);
+        analyzer.read(text.as_bytes(), None, |_| ()).unwrap();
+        assert_eq!(analyzer.counted, count_changes(&depths, 1, &comparison));
+        assert_eq!(analyzer.windows_counted, count_changes(&depths, 3, &comparison));
+    }
+
+    #[test]
+    fn reports_progress() {
+        let mut seen = Vec::new();
+        let mut analyzer = Analyzer::new(3, Comparison::default());
+        analyzer.read(include_str!("example.txt").as_bytes(), Some(4), |analyzer| {
+            seen.push((analyzer.samples, analyzer.counted));
+        }).unwrap();
+        assert_eq!(seen, vec![(4, 3), (8, 6)]);
+        let err = analyzer.read("7\nx\n".as_bytes(), None, |_| ()).unwrap_err();
+        assert!(matches!(err, Error::Parse(err) if err.line == 12));
+    }
+}
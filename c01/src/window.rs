@@ -0,0 +1,98 @@
+use std::collections::VecDeque;
+use std::ops::{Add, Sub};
+
+/// The latest `size` values and their sum, kept in a ring buffer that holds
+/// no more than that.
+pub struct Window<T> {
+    values: VecDeque<T>,
+    size: usize,
+    sum: T,
+}
+
+impl<T> Window<T>
+    where T: Copy + Default + Add<Output = T> + Sub<Output = T>
+{
+    /// Panics if `size` is zero.
+    pub fn new(size: usize) -> Window<T> {
+        assert!(size > 0, "a window must hold at least one value");
+        Window {
+            values: VecDeque::with_capacity(size + 1),
+            size,
+            sum: T::default(),
+        }
+    }
+
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Add a value, dropping the oldest one if the window was full, and
+    /// return the sum of the window if it's full now. The sum is updated
+    /// rather than recalculated, so this takes the same time whatever the
+    /// size.
+    pub fn push(&mut self, value: T) -> Option<T> {
+        self.values.push_back(value);
+        self.sum = self.sum + value;
+        if self.values.len() > self.size {
+            let oldest = self.values.pop_front().unwrap();
+            self.sum = self.sum - oldest;
+        }
+        if self.values.len() == self.size { Some(self.sum) } else { None }
+    }
+}
+
+/// An iterator over the sums of each run of `size` consecutive values from
+/// another iterator, found with a `Window`. An iterator with fewer than
+/// `size` values has no windows.
+pub struct RollingSums<I: Iterator> {
+    iter: I,
+    window: Window<I::Item>,
+}
+
+impl<I: Iterator> Iterator for RollingSums<I>
+    where I::Item: Copy + Default + Add<Output = I::Item> + Sub<Output = I::Item>
+{
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<I::Item> {
+        loop {
+            let value = self.iter.next()?;
+            if let Some(sum) = self.window.push(value) {
+                return Some(sum);
+            }
+        }
+    }
+}
+
+/// Adds `rolling_sums` to every iterator over numbers.
+pub trait RollingSumsExt: Iterator + Sized
+    where Self::Item: Copy + Default + Add<Output = Self::Item> + Sub<Output = Self::Item>
+{
+    /// The sums of each window of `size` consecutive values, e.g. `[1, 2, 3,
+    /// 4]` in windows of 2 gives `[3, 5, 7]`. Panics if `size` is zero.
+    fn rolling_sums(self, size: usize) -> RollingSums<Self> {
+        RollingSums {
+            iter: self,
+            window: Window::new(size),
+        }
+    }
+}
+
+impl<I: Iterator> RollingSumsExt for I
+    where I::Item: Copy + Default + Add<Output = I::Item> + Sub<Output = I::Item>
+{}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sums_each_window() {
+        let values = [1, 2, 3, 4, 5];
+        assert_eq!(values.iter().copied().rolling_sums(2).collect::<Vec<i32>>(), vec![3, 5, 7, 9]);
+        assert_eq!(values.iter().copied().rolling_sums(1).collect::<Vec<i32>>(), values);
+        assert_eq!(values.iter().copied().rolling_sums(5).collect::<Vec<i32>>(), vec![15]);
+        assert_eq!(values.iter().copied().rolling_sums(6).count(), 0);
+        assert_eq!([0.5, 1.5, 2.0].into_iter().rolling_sums(2).collect::<Vec<f64>>(), vec![2.0, 3.5]);
+    }
+}
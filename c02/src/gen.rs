@@ -0,0 +1,34 @@
+use aoc_common::Rng;
+
+pub const USAGE: &str = " [OPTIONS]
+
+Generate a random submarine course. SIZE is the number of steps (1000 by
+default).
+
+OPTIONS:
+ --max-units UNITS: Largest distance moved by a single step (default 9)
+ -h: Print this usage message and exit
+";
+
+/// Generate a course of `count` steps, each moving 1 to `max_units`. Up
+/// steps never take the submarine above the surface.
+pub fn course(rng: &mut Rng, count: usize, max_units: u32) -> String {
+    let mut depth = 0;
+    let mut text = String::new();
+    for _ in 0..count {
+        let units = rng.range(1, i64::from(max_units));
+        let direction = match rng.below(3) {
+            0 => "forward",
+            1 if depth >= units => {
+                depth -= units;
+                "up"
+            },
+            _ => {
+                depth += units;
+                "down"
+            },
+        };
+        text += &format!("{} {}\n", direction, units);
+    }
+    text
+}
@@ -0,0 +1,278 @@
+use std::io::BufRead;
+use std::str::FromStr;
+
+use aoc_common::render::{Color, Picture};
+use aoc_common::solver::Parts;
+use aoc_common::{debug, error, input, solver, Answer, Args, Error, Int, ParseError, Puzzle, Rng};
+
+pub mod gen;
+
+const USAGE: &str = " [OPTIONS] FILENAME
+
+Follow a submarine course.
+
+The file given by FILENAME contains a series of directional instructions on
+separate lines. A line can direct the submarine to go forward, down, or up.
+The submarine moves in two dimensions - horizontal (forward) and depth (down
+and up).
+
+This program follows the course given in the file and calculates the product of
+the final horizontal and depth positions.
+
+OPTIONS:
+ --render PATH: Draw the submarine's path, steering with aim unless only part
+                1 is run (see RENDERING below)
+ -h: Print this usage message and exit
+
+Use - as FILENAME to read from standard input.
+
+https://adventofcode.com/2021/day/2
+";
+
+pub struct Day;
+
+impl Puzzle for Day {
+    const DAY: u32 = 2;
+    const NAME: &'static str = "Dive!";
+    const USAGE: &'static str = USAGE;
+    const OPTIONS: &'static [&'static str] = &["--render"];
+    const GEN_USAGE: &'static str = gen::USAGE;
+    const GEN_OPTIONS: &'static [&'static str] = &["--max-units"];
+
+    type Input = Course;
+
+    fn parse(&self, text: &str, _args: &Args) -> Result<Course, Error> {
+        text.parse()
+    }
+
+    fn part1(&self, course: &Course) -> Answer {
+        Answer::new("Simple: Horiz * Depth", simple_product(course))
+    }
+
+    fn part2(&self, course: &Course) -> Answer {
+        Answer::new("With aim: Horiz * Depth", aim_product(course))
+    }
+
+    fn generate(&self, rng: &mut Rng, args: &Args) -> Result<String, Error> {
+        let count = solver::gen_size(args, 1000)?;
+        let max_units = args.parsed_value_in("--max-units", 1..)?.unwrap_or(9);
+        Ok(gen::course(rng, count, max_units))
+    }
+
+    fn render(&self, course: &Course, args: &Args) -> Result<Option<Picture>, Error> {
+        let with_aim = self.parts(args)? != Parts::One;
+        Ok(Some(render_path(&path(course, with_aim))))
+    }
+}
+
+/// Follow the course treating up and down as changes in depth, and return the
+/// product of the final horizontal position and depth.
+pub fn simple_product(course: &Course) -> Int {
+    let mut course = course.clone();
+    course.reset();
+    course.follow_simple();
+    course.horiz * course.depth
+}
+
+/// Follow the course treating up and down as changes in aim, and return the
+/// product of the final horizontal position and depth.
+pub fn aim_product(course: &Course) -> Int {
+    let mut course = course.clone();
+    course.reset();
+    course.follow_with_aim();
+    course.horiz * course.depth
+}
+
+/// The horizontal position and depth after each step of the course,
+/// starting from the surface, treating up and down as changes in aim if
+/// `with_aim`.
+pub fn path(course: &Course, with_aim: bool) -> Vec<(Int, Int)> {
+    let mut course = course.clone();
+    course.reset();
+    let mut path = vec![(Int::default(), Int::default())];
+    for step in course.steps.clone() {
+        course.take_step(step, with_aim);
+        path.push((course.horiz.clone(), course.depth.clone()));
+    }
+    path
+}
+
+/// The size of a path picture in cells.
+const PATH_WIDTH: usize = 100;
+const PATH_HEIGHT: usize = 40;
+
+/// Draw a path scaled to fit the picture, with the surface at the top. The
+/// start is marked S and the end E.
+pub fn render_path(path: &[(Int, Int)]) -> Picture {
+    let mut picture = Picture::new(PATH_WIDTH, PATH_HEIGHT);
+    let max_horiz = path.iter().map(|(horiz, _)| horiz.to_f64()).fold(1.0, f64::max);
+    let min_depth = path.iter().map(|(_, depth)| depth.to_f64()).fold(0.0, f64::min);
+    let max_depth = path.iter().map(|(_, depth)| depth.to_f64()).fold(min_depth + 1.0, f64::max);
+    let scale = |(horiz, depth): &(Int, Int)| {
+        ((horiz.to_f64() * (PATH_WIDTH - 1) as f64 / max_horiz) as i64,
+         ((depth.to_f64() - min_depth) * (PATH_HEIGHT - 1) as f64 / (max_depth - min_depth)) as i64)
+    };
+    let cells: Vec<(i64, i64)> = path.iter().map(scale).collect();
+    for pair in cells.windows(2) {
+        picture.line(pair[0], pair[1], '*', Color::BLUE);
+    }
+    if let (Some(&(x, y)), Some(&(end_x, end_y))) = (cells.first(), cells.last()) {
+        picture.set(x as usize, y as usize, 'S', Color::GREEN);
+        picture.set(end_x as usize, end_y as usize, 'E', Color::RED);
+    }
+    picture
+}
+
+#[derive(Clone)]
+pub struct Course {
+    pub steps: Vec<CourseStep>,
+    pub horiz: Int,
+    pub depth: Int,
+    pub aim: Int,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CourseStep {
+    Forward(i32),
+    Down(i32),
+    Up(i32),
+}
+
+impl Course {
+    pub fn from_reader<R: BufRead>(reader: R) -> Result<Course, Error> {
+        let mut steps: Vec<CourseStep> = Vec::new();
+        for (idx, line) in input::lines(reader)?.iter().enumerate() {
+            let line_no = idx + 1;
+            let parts: Vec<&str> = line.split(' ').collect();
+            if parts.len() < 2 {
+                return Err(ParseError::at_end(line_no, line, "expected a direction and a magnitude").into());
+            }
+            if parts.len() > 2 {
+                return Err(ParseError::at_token(line_no, line, parts[2], "unexpected text after the magnitude").into());
+            }
+            let direction = parts[0];
+            let magnitude = error::parse_number(line_no, line, parts[1], "a magnitude")?;
+            if direction == "forward" {
+                steps.push(CourseStep::Forward(magnitude));
+            } else if direction == "down" {
+                steps.push(CourseStep::Down(magnitude));
+            } else if direction == "up" {
+                steps.push(CourseStep::Up(magnitude));
+            } else {
+                let message = format!("unknown direction {:?}", direction);
+                return Err(ParseError::at_token(line_no, line, direction, &message).into());
+            }
+        }
+        Ok(Course {
+            steps,
+            horiz: Int::default(),
+            depth: Int::default(),
+            aim: Int::default(),
+        })
+    }
+    pub fn follow_simple(&mut self) {
+        for step in self.steps.clone() {
+            self.take_step(step, false);
+            debug!("{:?}: horizontal {}, depth {}", step, self.horiz, self.depth);
+        }
+    }
+    pub fn follow_with_aim(&mut self) {
+        for step in self.steps.clone() {
+            self.take_step(step, true);
+            debug!("{:?}: horizontal {}, depth {}, aim {}", step, self.horiz, self.depth, self.aim);
+        }
+    }
+    /// Take one step, treating up and down as changes in aim if `with_aim`
+    /// and as changes in depth otherwise.
+    pub fn take_step(&mut self, step: CourseStep, with_aim: bool) {
+        match step {
+            CourseStep::Forward(magnitude) => {
+                self.horiz += magnitude;
+                if with_aim {
+                    self.depth += self.aim.clone() * magnitude;
+                }
+            },
+            CourseStep::Down(magnitude) if with_aim => { self.aim += magnitude },
+            CourseStep::Up(magnitude) if with_aim => { self.aim -= magnitude },
+            CourseStep::Down(magnitude) => { self.depth += magnitude },
+            CourseStep::Up(magnitude) => { self.depth -= magnitude },
+        }
+    }
+    pub fn reset(&mut self) {
+        self.horiz = Int::default();
+        self.depth = Int::default();
+        self.aim = Int::default();
+    }
+}
+
+impl FromStr for Course {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Course, Error> {
+        Course::from_reader(s.as_bytes())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = include_str!("example.txt");
+
+    #[test]
+    fn parses_steps() {
+        let course: Course = EXAMPLE.parse().unwrap();
+        assert_eq!(course.steps.len(), 6);
+        assert_eq!(course.steps[0], CourseStep::Forward(5));
+        assert_eq!(course.steps[3], CourseStep::Up(3));
+    }
+
+    #[test]
+    fn follow_simple() {
+        let course = EXAMPLE.parse().unwrap();
+        assert_eq!(simple_product(&course), 150);
+    }
+
+    #[test]
+    fn follow_with_aim() {
+        let course = EXAMPLE.parse().unwrap();
+        assert_eq!(aim_product(&course), 900);
+    }
+
+    #[test]
+    fn path_ends_at_final_position() {
+        let course = EXAMPLE.parse().unwrap();
+        let simple = path(&course, false);
+        assert_eq!((simple.len(), simple[6].clone()), (7, (Int::from(15), Int::from(10))));
+        assert_eq!(path(&course, true)[6], (Int::from(15), Int::from(60)));
+        let text = render_path(&path(&course, true)).to_ascii();
+        assert!(text.starts_with('S'));
+        assert_eq!(text.lines().last().unwrap().trim_start(), "E");
+    }
+
+    #[test]
+    fn large_magnitudes() {
+        let course = "down 2000000000\nforward 2000000000\nforward 2000000000\n".parse().unwrap();
+        assert_eq!(simple_product(&course).to_string(), "8000000000000000000");
+        assert_eq!(aim_product(&course).to_string(), format!("32{}", "0".repeat(27)));
+    }
+
+    #[test]
+    fn unknown_direction() {
+        match "forward 5\nsideways 3\n".parse::<Course>() {
+            Err(Error::Parse(err)) => {
+                assert_eq!((err.line, err.column), (2, 1));
+                assert_eq!(err.text, "sideways");
+            },
+            other => panic!("expected a parse error, got {:?}", other.err()),
+        }
+    }
+
+    #[test]
+    fn generated_course_stays_underwater() {
+        let mut course: Course = gen::course(&mut Rng::new(1), 100, 9).parse().unwrap();
+        assert_eq!(course.steps.len(), 100);
+        course.follow_simple();
+        assert!(course.depth >= 0);
+    }
+}
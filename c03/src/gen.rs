@@ -0,0 +1,21 @@
+use aoc_common::Rng;
+
+pub const USAGE: &str = " [OPTIONS]
+
+Generate a random diagnostic report. SIZE is the number of values (1000 by
+default).
+
+OPTIONS:
+ --width BITS: Number of binary digits in each value, from 1 to 32 (default 12)
+ -h: Print this usage message and exit
+";
+
+/// Generate `count` random values of `width` binary digits each.
+pub fn report(rng: &mut Rng, count: usize, width: usize) -> String {
+    let mut text = String::new();
+    for _ in 0..count {
+        let value = rng.below(1 << width);
+        text += &format!("{:0width$b}\n", value, width = width);
+    }
+    text
+}
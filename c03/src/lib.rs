@@ -0,0 +1,283 @@
+use std::io::BufRead;
+use std::str::FromStr;
+
+use aoc_common::diff::Variant;
+use aoc_common::{debug, input, solver, Answer, Args, Error, ParseError, Puzzle, Rng};
+
+pub mod gen;
+
+const USAGE: &str = " [OPTIONS] FILENAME
+
+Calculate diagnostic values.
+
+The file given by FILENAME contains a series of numeric values written in
+binary separated by newlines.
+
+The \"gamma\" and \"epsilon\" values are found by determining the most common
+value for each bit position in the list of values. The product of these is the
+\"power consumption\".
+
+The \"life support\" value is the product of the \"O2\" and \"CO2\" values,
+which are determined by incrementally removing values whose bit does not match
+the most common corresponding bit in the list, starting with the most-
+significant bit.
+
+Use - as FILENAME to read from standard input.
+
+https://adventofcode.com/2021/day/3
+";
+
+pub struct Day;
+
+impl Puzzle for Day {
+    const DAY: u32 = 3;
+    const NAME: &'static str = "Binary Diagnostic";
+    const USAGE: &'static str = USAGE;
+    const GEN_USAGE: &'static str = gen::USAGE;
+    const GEN_OPTIONS: &'static [&'static str] = &["--width"];
+
+    type Input = Report;
+
+    fn parse(&self, text: &str, _args: &Args) -> Result<Report, Error> {
+        text.parse()
+    }
+
+    fn part1(&self, report: &Report) -> Answer {
+        let (gamma, epsilon) = gamma_epsilon(&report.data, report.size);
+        Answer::new("Power consumption", product(gamma, epsilon))
+            .with("gamma", gamma)
+            .with("epsilon", epsilon)
+    }
+
+    fn part2(&self, report: &Report) -> Answer {
+        match report.ratings() {
+            Some((o2, co2)) => Answer::new("Life support", product(o2, co2)).with("o2", o2).with("co2", co2),
+            None => Answer::new("Life support", "none").with("note", "no value is left after filtering"),
+        }
+    }
+
+    fn generate(&self, rng: &mut Rng, args: &Args) -> Result<String, Error> {
+        let count = solver::gen_size(args, 1000)?;
+        let width = args.parsed_value_in("--width", 1..=32)?.unwrap_or(12);
+        Ok(gen::report(rng, count, width))
+    }
+
+    fn variants(&self) -> Vec<Variant<Report>> {
+        vec![
+            Variant::new("sorted", 2, |report| {
+                let mut sorted = report.data.clone();
+                sorted.sort_unstable();
+                let o2 = sorted_life_support_value(&sorted, report.size, true);
+                let co2 = sorted_life_support_value(&sorted, report.size, false);
+                match (o2, co2) {
+                    (Some(o2), Some(co2)) => Answer::new("Life support", product(o2, co2)).with("o2", o2).with("co2", co2),
+                    _ => Answer::new("Life support", "none").with("note", "no value is left after filtering"),
+                }
+            }),
+        ]
+    }
+}
+
+/// A diagnostic report: a list of values that are each `size` bits wide.
+pub struct Report {
+    pub data: Vec<u32>,
+    pub size: usize,
+}
+
+impl Report {
+    pub fn from_reader<R: BufRead>(reader: R) -> Result<Report, Error> {
+        let mut data = Vec::new();
+        let mut size = 0;
+        for (idx, line) in input::lines(reader)?.iter().enumerate() {
+            let line_no = idx + 1;
+            if let Some((col, c)) = line.char_indices().find(|&(_, c)| c != '0' && c != '1') {
+                let message = format!("expected a binary digit, found {:?}", c);
+                return Err(ParseError::at_token(line_no, line, &line[col..col + c.len_utf8()], &message).into());
+            }
+            if line.is_empty() || line.len() > 32 {
+                return Err(ParseError::at_token(line_no, line, line, "expected 1 to 32 binary digits").into());
+            }
+            if idx > 0 && line.len() != size {
+                let message = format!("expected {} binary digits like the lines before", size);
+                return Err(ParseError::at_token(line_no, line, line, &message).into());
+            }
+            size = line.len();
+            let value = u32::from_str_radix(line, 2).unwrap();
+            data.push(value);
+        }
+        Ok(Report {
+            data,
+            size,
+        })
+    }
+    /// The product of gamma and epsilon, which can need all 64 bits for
+    /// 32-bit values.
+    pub fn power_consumption(&self) -> u64 {
+        let (gamma, epsilon) = gamma_epsilon(&self.data, self.size);
+        product(gamma, epsilon)
+    }
+    /// The O2 and CO2 values, or `None` if either can't be found.
+    pub fn ratings(&self) -> Option<(u32, u32)> {
+        let o2 = calc_life_support_value(&self.data, self.size, true)?;
+        let co2 = calc_life_support_value(&self.data, self.size, false)?;
+        Some((o2, co2))
+    }
+    /// The product of the O2 and CO2 values, which can need all 64 bits for
+    /// 32-bit values, or `None` if either can't be found.
+    pub fn life_support(&self) -> Option<u64> {
+        self.ratings().map(|(o2, co2)| product(o2, co2))
+    }
+}
+
+impl FromStr for Report {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Report, Error> {
+        Report::from_reader(s.as_bytes())
+    }
+}
+
+fn product(a: u32, b: u32) -> u64 {
+    u64::from(a) * u64::from(b)
+}
+
+pub fn gamma_epsilon(data: &[u32], size: usize) -> (u32, u32) {
+    let mut gamma: u32 = 0;
+    let mut epsilon: u32 = 0;
+    for bit in 0..size {
+        let mut z_ct = 0;
+        let mut o_ct = 0;
+        let mask = 1 << bit;
+        for val in data {
+            if val & mask != 0 {
+                o_ct += 1;
+            } else {
+                z_ct += 1;
+            }
+        }
+        if z_ct > o_ct {
+            gamma |= 0 << bit;
+            epsilon |= 1 << bit;
+        } else {
+            gamma |= 1 << bit;
+            epsilon |= 0 << bit;
+        }
+    }
+    (gamma, epsilon)
+}
+
+/// Filter `data` on each bit in turn, starting with the most significant,
+/// until one value is left. Returns `None` if none are left, which happens
+/// for the least common bit when every remaining value shares it.
+pub fn calc_life_support_value(data: &[u32], size: usize, use_most_common: bool) -> Option<u32> {
+    let mut filtered_data = data.to_vec();
+    for bit in (0..size).rev() {
+        let mask = 1 << bit;
+        let (gamma, epsilon) = gamma_epsilon(&filtered_data, size);
+        let bit_criteria = if use_most_common { gamma } else { epsilon };
+        let rating = if use_most_common { "O2" } else { "CO2" };
+        let wanted = u32::from(bit_criteria & mask != 0);
+        filtered_data.retain(|val| val & mask == bit_criteria & mask);
+        debug!("{} bit {}: kept values with a {} there, {} remain", rating, bit, wanted, filtered_data.len());
+        if filtered_data.len() <= 1 {
+            break;
+        }
+    }
+    filtered_data.first().copied()
+}
+
+/// Like `calc_life_support_value`, but for sorted data. The values left
+/// after filtering on the higher bits are a run of `sorted` with the zeros in
+/// the next bit before the ones, so each step only has to find where the ones
+/// start.
+pub fn sorted_life_support_value(sorted: &[u32], size: usize, use_most_common: bool) -> Option<u32> {
+    let mut remaining = sorted;
+    for bit in (0..size).rev() {
+        if remaining.len() <= 1 {
+            break;
+        }
+        let mask = 1 << bit;
+        let (zeros, ones) = remaining.split_at(remaining.partition_point(|val| val & mask == 0));
+        let keep_ones = if use_most_common { ones.len() >= zeros.len() } else { zeros.len() > ones.len() };
+        remaining = if keep_ones { ones } else { zeros };
+    }
+    remaining.first().copied()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = include_str!("example.txt");
+
+    #[test]
+    fn gamma_epsilon_uses_most_common_bits() {
+        let report: Report = EXAMPLE.parse().unwrap();
+        assert_eq!(report.size, 5);
+        assert_eq!(gamma_epsilon(&report.data, report.size), (22, 9));
+        assert_eq!(report.power_consumption(), 198);
+    }
+
+    #[test]
+    fn life_support_values() {
+        let report: Report = EXAMPLE.parse().unwrap();
+        assert_eq!(calc_life_support_value(&report.data, report.size, true), Some(23));
+        assert_eq!(calc_life_support_value(&report.data, report.size, false), Some(10));
+        assert_eq!(report.ratings(), Some((23, 10)));
+        assert_eq!(report.life_support(), Some(230));
+        let mut sorted = report.data.clone();
+        sorted.sort_unstable();
+        assert_eq!(sorted_life_support_value(&sorted, report.size, true), Some(23));
+        assert_eq!(sorted_life_support_value(&sorted, report.size, false), Some(10));
+    }
+
+    #[test]
+    fn rejects_non_binary_digit() {
+        match "00100\n11210\n".parse::<Report>() {
+            Err(Error::Parse(err)) => assert_eq!((err.line, err.column), (2, 3)),
+            other => panic!("expected a parse error, got {:?}", other.err()),
+        }
+    }
+
+    #[test]
+    fn rejects_mismatched_width() {
+        assert!("00100\n1110\n".parse::<Report>().is_err());
+    }
+
+    #[test]
+    fn generated_report_parses() {
+        let report: Report = gen::report(&mut Rng::new(1), 100, 7).parse().unwrap();
+        assert_eq!((report.data.len(), report.size), (100, 7));
+    }
+
+    #[test]
+    fn generated_32_bit_report_solves() {
+        let report: Report = gen::report(&mut Rng::new(2), 500, 32).parse().unwrap();
+        assert_eq!(report.size, 32);
+        let (gamma, epsilon) = gamma_epsilon(&report.data, report.size);
+        assert_eq!(gamma ^ epsilon, u32::MAX);
+        assert_eq!(report.power_consumption(), u64::from(gamma) * u64::from(epsilon));
+        let mut sorted = report.data.clone();
+        sorted.sort_unstable();
+        let o2 = sorted_life_support_value(&sorted, report.size, true).unwrap();
+        let co2 = sorted_life_support_value(&sorted, report.size, false).unwrap();
+        assert_eq!(report.life_support(), Some(u64::from(o2) * u64::from(co2)));
+    }
+
+    #[test]
+    fn co2_runs_out_of_values() {
+        // after the first bit, 000 and 001 remain and neither has a 1 next
+        let data = [0b000, 0b001, 0b101, 0b110, 0b111];
+        assert_eq!(calc_life_support_value(&data, 3, false), None);
+        assert_eq!(sorted_life_support_value(&data, 3, false), None);
+        assert_eq!(calc_life_support_value(&data, 3, true), Some(0b111));
+    }
+
+    #[test]
+    fn empty_report() {
+        let report: Report = "".parse().unwrap();
+        assert_eq!(calc_life_support_value(&report.data, report.size, true), None);
+        assert_eq!(sorted_life_support_value(&report.data, report.size, false), None);
+        assert_eq!(Day.part2(&report).value, "none".into());
+    }
+}
@@ -0,0 +1,29 @@
+use aoc_common::Rng;
+
+pub const USAGE: &str = " [OPTIONS]
+
+Generate a random bingo game: every number from 0 to MAX in a random order,
+followed by the boards. SIZE is the number of boards (100 by default).
+
+OPTIONS:
+ --max-number MAX: Largest number in the game, from 24 to 255 (default 99)
+ -h: Print this usage message and exit
+";
+
+/// Generate a game with `boards` boards, each holding 25 different numbers
+/// from 0 through `max_number`. Every number is called, so every board wins.
+pub fn game(rng: &mut Rng, boards: usize, max_number: u8) -> String {
+    let mut numbers: Vec<u8> = (0..=max_number).collect();
+    rng.shuffle(&mut numbers);
+    let mut text = numbers.iter().map(|n| n.to_string()).collect::<Vec<String>>().join(",");
+    text += "\n";
+    for _ in 0..boards {
+        rng.shuffle(&mut numbers);
+        text += "\n";
+        for row in numbers[..25].chunks(5) {
+            text += &row.iter().map(|n| format!("{:2}", n)).collect::<Vec<String>>().join(" ");
+            text += "\n";
+        }
+    }
+    text
+}
@@ -0,0 +1,410 @@
+use std::io::BufRead;
+use std::str::FromStr;
+
+use aoc_common::render::{Color, Picture};
+use aoc_common::solver::Parts;
+use aoc_common::{debug, error, info, input, solver, Answer, Args, Error, Grid, ParseError, Puzzle, Rng};
+
+pub mod gen;
+
+const USAGE: &str = " [OPTIONS] FILENAME
+
+Determine the outcome of a bingo game.
+
+The given file begins with a line of comma-separated numbers to be called. The
+rest of the file is a series of bingo boards.
+
+This program will determine which board wins first and what its score is.
+
+OPTIONS:
+ --render PATH: Draw every board as it stands when the first board wins, or
+                when the last board wins unless only part 1 is run, with the
+                marked numbers highlighted (see RENDERING below)
+ -h: Print this usage message and exit
+
+Use - as FILENAME to read from standard input.
+
+https://adventofcode.com/2021/day/4
+";
+
+pub struct Day;
+
+impl Puzzle for Day {
+    const DAY: u32 = 4;
+    const NAME: &'static str = "Giant Squid";
+    const USAGE: &'static str = USAGE;
+    const OPTIONS: &'static [&'static str] = &["--render"];
+    const GEN_USAGE: &'static str = gen::USAGE;
+    const GEN_OPTIONS: &'static [&'static str] = &["--max-number"];
+
+    type Input = BingoGame;
+
+    fn parse(&self, text: &str, _args: &Args) -> Result<BingoGame, Error> {
+        text.parse()
+    }
+
+    fn part1(&self, game: &BingoGame) -> Answer {
+        score_answer("Final score of first winning board", first_winner_score(game))
+    }
+
+    fn part2(&self, game: &BingoGame) -> Answer {
+        score_answer("Final score of final winning board", final_winner_score(game))
+    }
+
+    fn generate(&self, rng: &mut Rng, args: &Args) -> Result<String, Error> {
+        let boards = solver::gen_size(args, 100)?;
+        let max_number = args.parsed_value_in("--max-number", 24..)?.unwrap_or(99);
+        Ok(gen::game(rng, boards, max_number))
+    }
+
+    fn render(&self, game: &BingoGame, args: &Args) -> Result<Option<Picture>, Error> {
+        let first = self.parts(args)? == Parts::One;
+        Ok(Some(match game_at_win(game, first) {
+            Some((game, winner)) => render_boards(&game, Some(winner)),
+            None => render_boards(&final_game(game), None),
+        }))
+    }
+}
+
+fn score_answer(label: &str, score: Option<i32>) -> Answer {
+    match score {
+        Some(score) => Answer::new(label, score),
+        None => Answer::new(label, "none").with("note", "reached end of game without a winner"),
+    }
+}
+
+/// Play a fresh copy of the game until some board wins, and return that
+/// board's score. Returns `None` if no board ever wins.
+pub fn first_winner_score(game: &BingoGame) -> Option<i32> {
+    let mut game = game.clone();
+    loop {
+        game.advance();
+        if let Some(winner) = game.find_winner() {
+            return Some(winner.score);
+        }
+        if game.is_over() {
+            return None;
+        }
+    }
+}
+
+/// Play a fresh copy of the game until every number has been called, and
+/// return the score of the last board to win. Returns `None` if no board ever
+/// wins.
+pub fn final_winner_score(game: &BingoGame) -> Option<i32> {
+    let game = final_game(game);
+    game.latest_winner.map(|idx| game.boards[idx].score)
+}
+
+/// Play a fresh copy of the game until the first board wins, or with
+/// `first` false, until the last board to win does. Returns the game at that
+/// point and the index of the board that just won, or `None` if no board
+/// ever wins.
+pub fn game_at_win(game: &BingoGame, first: bool) -> Option<(BingoGame, usize)> {
+    let mut game = game.clone();
+    let mut last_win = None;
+    while !game.is_over() {
+        let previous = game.latest_winner;
+        game.advance();
+        if game.latest_winner != previous {
+            let winner = game.latest_winner?;
+            if first {
+                // when several boards win at once, the first of them is the
+                // one `first_winner_score` scores
+                let first_winner = game.boards.iter().position(|board| board.has_won)?;
+                return Some((game, first_winner));
+            }
+            last_win = Some((game.clone(), winner));
+        }
+    }
+    last_win
+}
+
+/// Play a fresh copy of the game until every number has been called.
+fn final_game(game: &BingoGame) -> BingoGame {
+    let mut game = game.clone();
+    while !game.is_over() {
+        game.advance();
+    }
+    game
+}
+
+/// Boards per row of a picture.
+const BOARDS_ACROSS: usize = 5;
+
+/// Draw the boards side by side, each under a heading with its number. A
+/// marked number is followed by `*` and shown in yellow, or in green on the
+/// `winner`'s board, whose heading also says that it won. Unmarked numbers
+/// are grey.
+pub fn render_boards(game: &BingoGame, winner: Option<usize>) -> Picture {
+    // each number takes 3 cells: 2 digits and room for a mark
+    let (board_width, board_height) = (5 * 3, 5 + 1);
+    let (pitch_x, pitch_y) = (board_width + 2, board_height + 1);
+    let rows = game.boards.len().div_ceil(BOARDS_ACROSS);
+    let across = game.boards.len().min(BOARDS_ACROSS);
+    let mut picture = Picture::new((across * pitch_x).saturating_sub(2), (rows * pitch_y).saturating_sub(1));
+    for (idx, board) in game.boards.iter().enumerate() {
+        let (left, top) = (idx % BOARDS_ACROSS * pitch_x, idx / BOARDS_ACROSS * pitch_y);
+        let won = winner == Some(idx);
+        let heading = if won { format!("#{} wins", idx + 1) } else { format!("#{}", idx + 1) };
+        picture.text(left, top, &heading, if won { Color::GREEN } else { Color::WHITE });
+        for ((x, y), &value) in board.values.enumerate() {
+            let (text, color) = match (board.marks[(x, y)], won) {
+                (true, true) => (format!("{:>2}*", value), Color::GREEN),
+                (true, false) => (format!("{:>2}*", value), Color::YELLOW),
+                (false, _) => (format!("{:>2}", value), Color::GREY),
+            };
+            picture.text(left + x * 3, top + 1 + y, &text, color);
+        }
+    }
+    picture
+}
+
+#[derive(Clone)]
+pub struct BingoBoard {
+    pub values: Grid<u8>,
+    pub marks: Grid<bool>,
+    pub score: i32,
+    pub has_won: bool,
+}
+
+impl Default for BingoBoard {
+    fn default() -> BingoBoard {
+        BingoBoard::new()
+    }
+}
+
+impl BingoBoard {
+    pub fn new() -> BingoBoard {
+        BingoBoard {
+            values: Grid::new(5, 5, 0),
+            marks: Grid::new(5, 5, false),
+            score: 0,
+            has_won: false,
+        }
+    }
+    /// A board with the given values and nothing marked.
+    pub fn with_values(values: Grid<u8>) -> BingoBoard {
+        BingoBoard {
+            marks: values.map(|_| false),
+            values,
+            score: 0,
+            has_won: false,
+        }
+    }
+    pub fn check_win(&self) -> bool {
+        let marks = &self.marks;
+        (0..marks.height()).any(|y| marks.row(y).all(|&mark| mark))
+            || (0..marks.width()).any(|x| marks.column(x).all(|&mark| mark))
+    }
+    pub fn call(&mut self, number: u8) {
+        for (pos, &value) in self.values.enumerate() {
+            if value == number {
+                self.marks[pos] = true;
+            }
+        }
+        if self.has_won {
+            // Don't recalculate score if we already won
+            return;
+        }
+        self.has_won = self.check_win();
+        self.score = self.local_score() * i32::from(number);
+    }
+    pub fn local_score(&self) -> i32 {
+        self.values.iter()
+            .zip(self.marks.iter())
+            .filter(|&(_, &marked)| !marked)
+            .map(|(&value, _)| i32::from(value))
+            .sum()
+    }
+}
+
+#[derive(Clone)]
+pub struct BingoGame {
+    pub numbers: Vec<u8>,
+    pub boards: Vec<BingoBoard>,
+    pub number_idx: usize,
+    pub latest_winner: Option<usize>,
+}
+
+impl BingoGame {
+    pub fn from_reader<R: BufRead>(reader: R) -> Result<BingoGame, Error> {
+        let mut numbers = Vec::new();
+        let mut boards = Vec::new();
+
+        let mut cur_rows = Vec::new();
+
+        let lines = input::lines(reader)?;
+        for (idx, line) in lines.iter().enumerate() {
+            let line_no = idx + 1;
+            if idx == 0 {
+                for value in line.split(',') {
+                    numbers.push(error::parse_number(line_no, line, value, "a number from 0 to 255")?);
+                }
+                continue;
+            }
+            if line.is_empty() {
+                if !cur_rows.is_empty() {
+                    return Err(ParseError::at_end(line_no, line, "expected 5 rows in each board").into());
+                }
+                continue;
+            }
+
+            let row = line.split_whitespace()
+                .map(|v| error::parse_number(line_no, line, v, "a number from 0 to 255"))
+                .collect::<Result<Vec<u8>, ParseError>>()?;
+            if row.len() != 5 {
+                let message = format!("expected 5 numbers in each row, found {}", row.len());
+                return Err(ParseError::at_token(line_no, line, line, &message).into());
+            }
+            cur_rows.push(row);
+            if cur_rows.len() == 5 {
+                // every row has 5 numbers, so the rows always make a grid
+                let values = Grid::from_rows(std::mem::take(&mut cur_rows)).unwrap();
+                boards.push(BingoBoard::with_values(values));
+            }
+        }
+
+        if !cur_rows.is_empty() {
+            let last = lines.last().unwrap();
+            return Err(ParseError::at_end(lines.len(), last, "expected 5 rows in each board").into());
+        }
+
+        Ok(BingoGame {
+            numbers,
+            boards,
+            number_idx: 0,
+            latest_winner: None,
+        })
+    }
+    pub fn find_winner(&self) -> Option<&BingoBoard> {
+        self.boards.iter().find(|board| board.has_won)
+    }
+    pub fn is_over(&self) -> bool {
+        self.number_idx >= self.numbers.len()
+    }
+    pub fn advance(&mut self) {
+        if self.is_over() {
+            return;
+        }
+        let number = self.numbers[self.number_idx];
+        self.number_idx += 1;
+        let mut marked_on = Vec::new();
+        for (idx, board) in self.boards.iter_mut().enumerate() {
+            if board.values.iter().any(|&value| value == number) {
+                marked_on.push(format!("#{}", idx + 1));
+            }
+            let had_won = board.has_won;
+            board.call(number);
+            if !had_won && board.has_won {
+                self.latest_winner = Some(idx);
+                info!("board #{} wins on {} with a score of {}", idx + 1, number, board.score);
+            }
+        }
+        debug!("called {}: marked on {}", number, if marked_on.is_empty() { "no boards".to_string() } else { marked_on.join(", ") });
+    }
+}
+
+impl FromStr for BingoGame {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<BingoGame, Error> {
+        BingoGame::from_reader(s.as_bytes())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = include_str!("example.txt");
+
+    #[test]
+    fn parses_numbers_and_boards() {
+        let game: BingoGame = EXAMPLE.parse().unwrap();
+        assert_eq!(game.numbers.len(), 27);
+        assert_eq!(game.boards.len(), 3);
+        assert_eq!(game.boards[1].values.row(2).copied().collect::<Vec<u8>>(), vec![19, 8, 7, 25, 23]);
+    }
+
+    #[test]
+    fn check_win_on_row_and_column() {
+        let mut board = BingoBoard::new();
+        assert!(!board.check_win());
+        for col in 0..5 {
+            board.marks[(col, 2)] = true;
+        }
+        assert!(board.check_win());
+
+        let mut board = BingoBoard::new();
+        for row in 0..5 {
+            board.marks[(3, row)] = true;
+        }
+        assert!(board.check_win());
+
+        let mut board = BingoBoard::new();
+        for i in 0..5 {
+            board.marks[(i, i)] = true;
+        }
+        assert!(!board.check_win(), "diagonals don't count");
+    }
+
+    #[test]
+    fn first_winner() {
+        let game = EXAMPLE.parse().unwrap();
+        assert_eq!(first_winner_score(&game), Some(4512));
+    }
+
+    #[test]
+    fn final_winner() {
+        let game = EXAMPLE.parse().unwrap();
+        assert_eq!(final_winner_score(&game), Some(1924));
+    }
+
+    #[test]
+    fn renders_winning_board() {
+        let game = EXAMPLE.parse().unwrap();
+        let (game, winner) = game_at_win(&game, true).unwrap();
+        assert_eq!(winner, 2);
+        let text = render_boards(&game, Some(winner)).to_ascii();
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines[0], "#1               #2               #3 wins");
+        assert_eq!(lines[1], "22 13 17*11* 0*   3 15  0* 2*22   14*21*17*24* 4*");
+
+        let (_, last) = game_at_win(&EXAMPLE.parse().unwrap(), false).unwrap();
+        assert_eq!(last, 1);
+    }
+
+    #[test]
+    fn simultaneous_winners_render_the_scored_board() {
+        let board = |offset: u8| {
+            let mut rows = vec!["1 2 3 4 5".to_string()];
+            for row in 0..4 {
+                let values: Vec<String> = (0..5).map(|col| (offset + row * 5 + col).to_string()).collect();
+                rows.push(values.join(" "));
+            }
+            rows.join("\n")
+        };
+        let text = format!("1,2,3,4,5\n\n{}\n\n{}\n", board(10), board(40));
+        let game: BingoGame = text.parse().unwrap();
+        let (won, winner) = game_at_win(&game, true).unwrap();
+        assert_eq!(winner, 0);
+        assert_eq!(first_winner_score(&game), Some(won.boards[winner].score));
+        assert_eq!(game_at_win(&game, false).unwrap().1, 1);
+    }
+
+    #[test]
+    fn rejects_short_row() {
+        match "1,2,3\n\n1 2 3 4\n".parse::<BingoGame>() {
+            Err(Error::Parse(err)) => assert_eq!(err.line, 3),
+            other => panic!("expected a parse error, got {:?}", other.err()),
+        }
+    }
+
+    #[test]
+    fn generated_game_has_a_winner() {
+        let game: BingoGame = gen::game(&mut Rng::new(1), 3, 30).parse().unwrap();
+        assert_eq!((game.numbers.len(), game.boards.len()), (31, 3));
+        assert!(final_winner_score(&game).is_some());
+    }
+}
@@ -0,0 +1,46 @@
+use aoc_common::Rng;
+
+pub const USAGE: &str = " [OPTIONS]
+
+Generate random horizontal, vertical and 45-degree diagonal line segments.
+SIZE is the number of segments (500 by default).
+
+OPTIONS:
+ --max-coord MAX: Largest X or Y coordinate, at least 1 (default 999)
+ --diagonal-percent PERCENT: Share of the segments that are diagonal
+                             (default 25)
+ -h: Print this usage message and exit
+";
+
+/// Two different coordinates from 0 through `max_coord`.
+fn two_coords(rng: &mut Rng, max_coord: i64) -> (i64, i64) {
+    let a = rng.range(0, max_coord);
+    let b = (a + rng.range(1, max_coord)) % (max_coord + 1);
+    (a, b)
+}
+
+/// Generate `count` segments with coordinates from 0 through `max_coord`,
+/// where `diagonal_percent` percent of them are diagonal and the rest are
+/// split between horizontal and vertical.
+pub fn segments(rng: &mut Rng, count: usize, max_coord: u32, diagonal_percent: u32) -> String {
+    let max_coord = i64::from(max_coord);
+    let mut text = String::new();
+    for _ in 0..count {
+        let (x1, x2) = two_coords(rng, max_coord);
+        let (y1, y2) = if rng.below(100) < u64::from(diagonal_percent) {
+            let length = (x2 - x1).abs();
+            let y1 = rng.range(0, max_coord - length);
+            if rng.chance(0.5) { (y1, y1 + length) } else { (y1 + length, y1) }
+        } else {
+            let y = rng.range(0, max_coord);
+            (y, y)
+        };
+        // transpose half of the straight segments to make them vertical
+        if y1 == y2 && rng.chance(0.5) {
+            text += &format!("{},{} -> {},{}\n", y1, x1, y2, x2);
+        } else {
+            text += &format!("{},{} -> {},{}\n", x1, y1, x2, y2);
+        }
+    }
+    text
+}
@@ -0,0 +1,372 @@
+use aoc_common::diff::Variant;
+use aoc_common::render::{Color, Picture};
+use aoc_common::solver::{self, Parts};
+use aoc_common::{error, info, trace, input, Answer, Args, Error, Grid, ParseError, Puzzle, Rng};
+use std::collections::{HashMap, HashSet};
+use std::cmp::{min, max};
+use std::io::BufRead;
+
+pub mod gen;
+
+const USAGE: &str = " [OPTIONS] FILENAME
+
+Find intersections.
+
+Each line of FILENAME represents X/Y endpoints of lines. This program will find
+all discrete points in a grid where two or more lines intersect. By default,
+horizontal and vertical lines are considered. Then 45-degree diagonals are also
+considered. With -d, only the count that includes diagonals is reported.
+
+OPTIONS:
+ -d, --diagonals: Only report the count including 45-degree diagonal lines
+                  (same as --part 2)
+ --render PATH: Draw a map of how many lines cover each point, including
+                diagonals unless only part 1 is run (see RENDERING below)
+ -h: Print this usage message and exit
+
+Use - as FILENAME to read from standard input.
+
+https://adventofcode.com/2021/day/5
+";
+
+pub struct Day;
+
+impl Puzzle for Day {
+    const DAY: u32 = 5;
+    const NAME: &'static str = "Hydrothermal Venture";
+    const USAGE: &'static str = USAGE;
+    const FLAGS: &'static [&'static str] = &["-d|--diagonals"];
+    const OPTIONS: &'static [&'static str] = &["--render"];
+    const GEN_USAGE: &'static str = gen::USAGE;
+    const GEN_OPTIONS: &'static [&'static str] = &["--max-coord", "--diagonal-percent"];
+
+    type Input = Vec<Line>;
+
+    fn parse(&self, text: &str, _args: &Args) -> Result<Vec<Line>, Error> {
+        load_lines(text.as_bytes())
+    }
+
+    fn part1(&self, lines: &Vec<Line>) -> Answer {
+        Answer::new("# intersections", count_intersections(lines, false))
+    }
+
+    fn part2(&self, lines: &Vec<Line>) -> Answer {
+        Answer::new("# intersections with diagonals", count_intersections(lines, true))
+    }
+
+    fn parts(&self, args: &Args) -> Result<Parts, Error> {
+        // -d asks for the count that includes diagonals, which is part 2
+        if args.has_flag("-d") {
+            Ok(Parts::Two)
+        } else {
+            solver::selected_parts(args)
+        }
+    }
+
+    fn generate(&self, rng: &mut Rng, args: &Args) -> Result<String, Error> {
+        let count = solver::gen_size(args, 500)?;
+        let max_coord = args.parsed_value_in("--max-coord", 1..)?.unwrap_or(999);
+        let diagonal_percent = args.parsed_value_in("--diagonal-percent", 0..=100)?.unwrap_or(25);
+        Ok(gen::segments(rng, count, max_coord, diagonal_percent))
+    }
+
+    fn variants(&self) -> Vec<Variant<Vec<Line>>> {
+        vec![
+            Variant::new("coverage", 1, |lines| {
+                Answer::new("# intersections", count_covered_points(lines, false))
+            }),
+            Variant::new("coverage", 2, |lines| {
+                Answer::new("# intersections with diagonals", count_covered_points(lines, true))
+            }),
+        ]
+    }
+
+    fn render(&self, lines: &Vec<Line>, args: &Args) -> Result<Option<Picture>, Error> {
+        let allow_diag = self.parts(args)? != Parts::One;
+        let (coverage, _) = coverage_map(lines, allow_diag)?;
+        Ok(Some(render_coverage(&coverage)))
+    }
+}
+
+/// Read line segments, one per line. Pass `text.as_bytes()` to read from a
+/// string.
+pub fn load_lines<R: BufRead>(reader: R) -> Result<Vec<Line>, Error> {
+    let mut lines = Vec::new();
+
+    for (idx, file_line) in input::lines(reader)?.iter().enumerate() {
+        let line_no = idx + 1;
+        let (start, end) = file_line.split_once(" -> ").ok_or_else(|| {
+            ParseError::at_token(line_no, file_line, file_line, "expected two points separated by \" -> \"")
+        })?;
+        let (x1, y1) = parse_point(line_no, file_line, start)?;
+        let (x2, y2) = parse_point(line_no, file_line, end)?;
+        let line = Line(x1, y1, x2, y2);
+        lines.push(line);
+    }
+
+    Ok(lines)
+}
+
+fn parse_point(line_no: usize, file_line: &str, point: &str) -> Result<(i32, i32), ParseError> {
+    let (x, y) = point.split_once(',').ok_or_else(|| {
+        ParseError::at_token(line_no, file_line, point, "expected a point like 0,9")
+    })?;
+    let x = error::parse_number(line_no, file_line, x, "an x coordinate")?;
+    let y = error::parse_number(line_no, file_line, y, "a y coordinate")?;
+    Ok((x, y))
+}
+
+/// The lines that are counted: horizontal and vertical ones, and with
+/// `allow_diag`, 45-degree diagonals too.
+fn counted_lines(lines: &[Line], allow_diag: bool) -> Vec<&Line> {
+    let counted: Vec<&Line> = lines.iter()
+        .filter(|line| {
+            let counts = line.is_cardinal() || (allow_diag && line.is_diagonal());
+            if !counts {
+                trace!("skipping {:?}", line);
+            }
+            counts
+        })
+        .collect();
+    info!("counting {} of {} lines", counted.len(), lines.len());
+    counted
+}
+
+/// The most points a coverage map can hold. The counts themselves only
+/// keep the points that lines cover, so this only limits --render.
+pub const MAX_RENDER_POINTS: u64 = 4_000_000;
+
+/// A grid just big enough to hold every point on `lines`, with every cell
+/// set to `fill`, along with the point that sits at its top left. It's an
+/// error if that would be more than `MAX_RENDER_POINTS`.
+fn vent_map<T: Clone>(lines: &[&Line], fill: T) -> Result<(Grid<T>, (i32, i32)), Error> {
+    if lines.is_empty() {
+        return Ok((Grid::new(0, 0, fill), (0, 0)));
+    }
+    let min_x = lines.iter().map(|line| line.min_x()).min().unwrap();
+    let max_x = lines.iter().map(|line| line.max_x()).max().unwrap();
+    let min_y = lines.iter().map(|line| line.min_y()).min().unwrap();
+    let max_y = lines.iter().map(|line| line.max_y()).max().unwrap();
+    let width = (i64::from(max_x) - i64::from(min_x) + 1) as u64;
+    let height = (i64::from(max_y) - i64::from(min_y) + 1) as u64;
+    // the span of i32 coordinates is up to 2^32 each way, so this can overflow
+    if width.checked_mul(height).is_none_or(|points| points > MAX_RENDER_POINTS) {
+        let message = format!("the vents span {}x{} points, more than the {} that can be rendered", width, height, MAX_RENDER_POINTS);
+        return Err(Error::Render(message));
+    }
+    Ok((Grid::new(width as usize, height as usize, fill), (min_x, min_y)))
+}
+
+/// Where a point is in a map whose top left is `origin`.
+fn map_position((x, y): (i32, i32), origin: (i32, i32)) -> (usize, usize) {
+    ((i64::from(x) - i64::from(origin.0)) as usize, (i64::from(y) - i64::from(origin.1)) as usize)
+}
+
+pub fn count_intersections(lines: &[Line], allow_diag: bool) -> usize {
+    let lines = counted_lines(lines, allow_diag);
+    let mut intersections = HashSet::new();
+    // yeah, it's O(n^2), but n is only 500
+    for i in 0..lines.len() {
+        for j in 0..lines.len() {
+            if i == j { continue; }
+            if !overlap(lines[i], lines[j]) { continue; }
+
+            for (jx, jy) in lines[j].points() {
+                if lines[i].contains_point(jx, jy) && intersections.insert((jx, jy)) {
+                    trace!("{:?} and {:?} cross at {},{}", lines[i], lines[j], jx, jy);
+                }
+            }
+        }
+    }
+    intersections.len()
+}
+
+/// Like `count_intersections`, but counts how many lines cover each point
+/// instead of comparing every pair of lines.
+pub fn count_covered_points(lines: &[Line], allow_diag: bool) -> usize {
+    coverage(lines, allow_diag).values().filter(|&&count| count > 1).count()
+}
+
+/// How many of the counted lines cover each point that any of them cover.
+pub fn coverage(lines: &[Line], allow_diag: bool) -> HashMap<(i32, i32), usize> {
+    let mut coverage = HashMap::new();
+    for line in counted_lines(lines, allow_diag) {
+        for point in line.points() {
+            *coverage.entry(point).or_insert(0) += 1;
+        }
+    }
+    coverage
+}
+
+/// Like `coverage`, but as a map of every point the lines span, along with
+/// the point at the map's top left. It's an error if the map would be more
+/// than `MAX_RENDER_POINTS`.
+pub fn coverage_map(lines: &[Line], allow_diag: bool) -> Result<(Grid<usize>, (i32, i32)), Error> {
+    let (mut map, origin) = vent_map(&counted_lines(lines, allow_diag), 0)?;
+    for (point, count) in coverage(lines, allow_diag) {
+        map[map_position(point, origin)] = count;
+    }
+    Ok((map, origin))
+}
+
+/// Draw a coverage map the way the puzzle does, with a dot where there's no
+/// line and otherwise the number of lines. Points where lines cross stand
+/// out in color.
+pub fn render_coverage(coverage: &Grid<usize>) -> Picture {
+    let mut picture = Picture::new(coverage.width(), coverage.height());
+    picture.background = Color::GREY.dimmed(40);
+    for ((x, y), &count) in coverage.enumerate() {
+        match count {
+            0 => picture.set(x, y, '.', picture.background),
+            1 => picture.set(x, y, '1', Color::BLUE),
+            2..=9 => picture.set(x, y, char::from_digit(count as u32, 10).unwrap(), if count == 2 { Color::ORANGE } else { Color::RED }),
+            _ => picture.set(x, y, '+', Color::RED),
+        }
+    }
+    picture
+}
+
+pub fn overlap(line1: &Line, line2: &Line) -> bool {
+    (line1.min_x() <= line2.max_x() && line1.max_x() >= line2.min_x()) && (line1.min_y() <= line2.max_y() && line1.max_y() >= line2.min_y())
+}
+
+#[derive(Debug)]
+pub struct Line(pub i32, pub i32, pub i32, pub i32);
+
+impl Line {
+    pub fn min_x(&self) -> i32 { min(self.0, self.2) }
+    pub fn max_x(&self) -> i32 { max(self.0, self.2) }
+    pub fn min_y(&self) -> i32 { min(self.1, self.3) }
+    pub fn max_y(&self) -> i32 { max(self.1, self.3) }
+
+    pub fn is_horizontal(&self) -> bool { self.1 == self.3 }
+    pub fn is_vertical(&self) -> bool { self.0 == self.2 }
+    pub fn is_diagonal(&self) -> bool { self.max_x() - self.min_x() == self.max_y() - self.min_y() }
+    pub fn is_cardinal(&self) -> bool { self.is_horizontal() || self.is_vertical() }
+
+    /// Every point from one end of the line to the other, including both.
+    /// Only horizontal, vertical and 45-degree lines are made of grid
+    /// points, so any other line gives none.
+    pub fn points(&self) -> Vec<(i32, i32)> {
+        if !self.is_cardinal() && !self.is_diagonal() {
+            return Vec::new();
+        }
+        let mut x = self.0;
+        let mut y = self.1;
+        let mut points = Vec::new();
+        let x_dir = if self.0 < self.2 { 1 } else { -1 };
+        let y_dir = if self.1 < self.3 { 1 } else { -1 };
+
+        loop {
+            points.push((x, y));
+            if x == self.2 && y == self.3 { break }
+            if self.is_horizontal() {
+                x += x_dir;
+            } else if self.is_vertical() {
+                y += y_dir;
+            } else if self.is_diagonal() {
+                x += x_dir;
+                y += y_dir;
+            }
+        }
+
+        points
+    }
+
+    pub fn contains_point(&self, x: i32, y: i32) -> bool {
+        if self.is_horizontal() {
+            y == self.1 && x >= self.min_x() && x <= self.max_x()
+        } else if self.is_vertical() {
+            x == self.0 && y >= self.min_y() && y <= self.max_y()
+        } else if self.is_diagonal() {
+            x >= self.min_x() && x <= self.max_x() && y >= self.min_y() && y <= self.max_y()
+                && (x - self.0).abs() == (y - self.1).abs()
+        } else { false }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = include_str!("example.txt");
+
+    #[test]
+    fn points_include_both_ends() {
+        assert_eq!(Line(1, 1, 1, 3).points(), vec![(1, 1), (1, 2), (1, 3)]);
+        assert_eq!(Line(9, 7, 7, 7).points(), vec![(9, 7), (8, 7), (7, 7)]);
+        assert_eq!(Line(9, 7, 7, 9).points(), vec![(9, 7), (8, 8), (7, 9)]);
+        assert_eq!(Line(4, 4, 4, 4).points(), vec![(4, 4)]);
+    }
+
+    #[test]
+    fn other_angles_have_no_points() {
+        assert!(Line(0, 0, 1, 2).points().is_empty());
+        assert!(Line(5, 0, 0, 1).points().is_empty());
+    }
+
+    #[test]
+    fn contains_point() {
+        let line = Line(1, 1, 3, 3);
+        assert!(line.contains_point(2, 2));
+        assert!(line.contains_point(3, 3));
+        assert!(!line.contains_point(2, 1));
+    }
+
+    #[test]
+    fn cardinal_intersections() {
+        let lines = load_lines(EXAMPLE.as_bytes()).unwrap();
+        assert_eq!(count_intersections(&lines, false), 5);
+    }
+
+    #[test]
+    fn intersections_with_diagonals() {
+        let lines = load_lines(EXAMPLE.as_bytes()).unwrap();
+        assert_eq!(count_intersections(&lines, true), 12);
+        assert_eq!(count_covered_points(&lines, true), 12);
+    }
+
+    #[test]
+    fn renders_like_the_puzzle() {
+        let lines = load_lines(EXAMPLE.as_bytes()).unwrap();
+        let text = render_coverage(&coverage_map(&lines, false).unwrap().0).to_ascii();
+        assert!(text.starts_with(".......1..\n..1....1..\n..1....1..\n.......1..\n.112111211\n"), "{}", text);
+    }
+
+    #[test]
+    fn far_apart_lines_stay_sparse() {
+        let lines = load_lines("0,0 -> 0,5\n0,2 -> 3,2\n2000000000,2000000000 -> 2000000000,2000000001\n".as_bytes())
+            .unwrap();
+        assert_eq!(count_intersections(&lines, false), 1);
+        assert_eq!(count_covered_points(&lines, true), 1);
+        assert!(matches!(coverage_map(&lines, false), Err(Error::Render(_))));
+    }
+
+    #[test]
+    fn refuses_to_render_the_whole_plane() {
+        let text = "-2147483648,-2147483648 -> -2147483648,-2147483647\n2147483647,2147483647 -> 2147483646,2147483647\n";
+        let lines = load_lines(text.as_bytes()).unwrap();
+        assert_eq!(count_covered_points(&lines, false), 0);
+        match coverage_map(&lines, false) {
+            Err(err) => assert!(err.to_string().contains("4294967296x4294967296"), "{}", err),
+            Ok(_) => panic!("expected the map to be too big"),
+        }
+    }
+
+    #[test]
+    fn rejects_bad_point() {
+        match load_lines("0,9 -> 5,9\n8,0 -> 0,x8\n".as_bytes()) {
+            Err(Error::Parse(err)) => {
+                assert_eq!((err.line, err.column), (2, 10));
+                assert_eq!(err.text, "x8");
+            },
+            other => panic!("expected a parse error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn generated_segments_parse() {
+        let lines = load_lines(gen::segments(&mut Rng::new(1), 100, 20, 50).as_bytes()).unwrap();
+        assert_eq!(lines.len(), 100);
+    }
+}
@@ -0,0 +1,16 @@
+use aoc_common::Rng;
+
+pub const USAGE: &str = " [OPTIONS]
+
+Generate a random school of lanternfish. SIZE is the number of fish (300 by
+default), each with a timer from 1 to 5.
+
+OPTIONS:
+ -h: Print this usage message and exit
+";
+
+/// Generate `count` comma-separated timers from 1 through 5.
+pub fn school(rng: &mut Rng, count: usize) -> String {
+    let timers: Vec<String> = (0..count).map(|_| rng.range(1, 5).to_string()).collect();
+    format!("{}\n", timers.join(","))
+}
@@ -0,0 +1,153 @@
+use std::io::BufRead;
+use std::ops::AddAssign;
+use std::str::FromStr;
+
+use aoc_common::log::{self, Level};
+use aoc_common::{debug, input, solver, Answer, Args, Error, Int, ParseError, Puzzle, Rng};
+
+pub mod gen;
+
+const USAGE: &str = " [OPTIONS] FILENAME
+
+Model population of lanternfish, starting from the initial state in the given
+FILENAME, after 80 days and after 256 days.
+
+OPTIONS:
+ --days DAYS: Model the given number of DAYS instead
+ -h: Print this usage message and exit
+
+Use - as FILENAME to read from standard input.
+
+https://adventofcode.com/2021/day/6
+";
+
+pub struct Day;
+
+impl Puzzle for Day {
+    const DAY: u32 = 6;
+    const NAME: &'static str = "Lanternfish";
+    const USAGE: &'static str = USAGE;
+    const OPTIONS: &'static [&'static str] = &["--days"];
+    const GEN_USAGE: &'static str = gen::USAGE;
+    const ANSWER_OPTIONS: &'static [&'static str] = &["--days"];
+
+    type Input = School;
+
+    fn parse(&self, text: &str, args: &Args) -> Result<School, Error> {
+        let mut school: School = text.parse()?;
+        school.days = args.parsed_value("--days")?;
+        Ok(school)
+    }
+
+    fn part1(&self, school: &School) -> Answer {
+        let days = school.days.unwrap_or(80);
+        Answer::new("Total lanternfish", school.population_after(days)).with("days", days)
+    }
+
+    fn part2(&self, school: &School) -> Answer {
+        let days = school.days.unwrap_or(256);
+        Answer::new("Total lanternfish", school.population_after(days)).with("days", days)
+    }
+
+    fn generate(&self, rng: &mut Rng, args: &Args) -> Result<String, Error> {
+        Ok(gen::school(rng, solver::gen_size(args, 300)?))
+    }
+}
+
+/// A school of lanternfish, counted by the number of days left on their
+/// reproduction timers.
+pub struct School {
+    pub counts: [u64; 9],
+    /// Number of days to model in place of the puzzle's, if given on the
+    /// command line.
+    pub days: Option<u64>,
+}
+
+impl School {
+    pub fn from_reader<R: BufRead>(reader: R) -> Result<School, Error> {
+        input::read_all(reader)?.parse()
+    }
+    /// The number of fish after `days` days. The population grows
+    /// exponentially, so it's counted with `Int` to stay exact however many
+    /// days are modeled.
+    pub fn population_after(&self, days: u64) -> Int {
+        let mut counts = self.counts.map(Int::from);
+        for day in 0..days {
+            advance(&mut counts);
+            if log::enabled(Level::Debug) {
+                debug!("after day {}: {} fish", day + 1, counts.iter().cloned().sum::<Int>());
+            }
+        }
+        counts.into_iter().sum()
+    }
+}
+
+impl FromStr for School {
+    type Err = Error;
+
+    fn from_str(data: &str) -> Result<School, Error> {
+        let mut counts = [0u64; 9];
+        for value in data.trim().split(',') {
+            let timer = value.parse::<usize>().ok().filter(|&timer| timer < counts.len());
+            match timer {
+                Some(timer) => counts[timer] += 1,
+                None => {
+                    let message = format!("expected a timer from 0 to 8, found {:?}", value);
+                    return Err(ParseError::in_text(data, value, &message).into());
+                },
+            }
+        }
+        Ok(School {
+            counts,
+            days: None,
+        })
+    }
+}
+
+pub fn advance<T: Clone + AddAssign>(counts: &mut [T; 9]) {
+    counts.rotate_left(1);
+    let repros = counts[8].clone();
+    counts[6] += repros;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = include_str!("example.txt");
+
+    #[test]
+    fn advance_resets_and_spawns() {
+        let mut counts = [1, 2, 0, 0, 0, 0, 0, 3, 4];
+        advance(&mut counts);
+        assert_eq!(counts, [2, 0, 0, 0, 0, 0, 4, 4, 1]);
+    }
+
+    #[test]
+    fn population() {
+        let school: School = EXAMPLE.parse().unwrap();
+        assert_eq!(school.population_after(18), 26);
+        assert_eq!(school.population_after(80), 5934);
+        assert_eq!(school.population_after(256), 26984457539);
+    }
+
+    #[test]
+    fn population_beyond_u64() {
+        let school: School = EXAMPLE.parse().unwrap();
+        assert_eq!(school.population_after(500).to_string(), "45920814377410229085");
+    }
+
+    #[test]
+    fn rejects_timer_out_of_range() {
+        match "3,4,3,1,9\n".parse::<School>() {
+            Err(Error::Parse(err)) => assert_eq!((err.line, err.column), (1, 9)),
+            other => panic!("expected a parse error, got {:?}", other.err()),
+        }
+    }
+
+    #[test]
+    fn generated_school_parses() {
+        let school: School = gen::school(&mut Rng::new(1), 100).parse().unwrap();
+        assert_eq!(school.population_after(0), 100);
+    }
+}
@@ -0,0 +1,18 @@
+use aoc_common::Rng;
+
+pub const USAGE: &str = " [OPTIONS]
+
+Generate random crab positions. SIZE is the number of crabs (1000 by default).
+
+OPTIONS:
+ --max-position MAX: Largest horizontal position (default 1999)
+ -h: Print this usage message and exit
+";
+
+/// Generate `count` comma-separated positions from 0 through `max_position`.
+pub fn positions(rng: &mut Rng, count: usize, max_position: u32) -> String {
+    let positions: Vec<String> = (0..count)
+        .map(|_| rng.range(0, i64::from(max_position)).to_string())
+        .collect();
+    format!("{}\n", positions.join(","))
+}
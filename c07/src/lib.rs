@@ -0,0 +1,205 @@
+use std::io::BufRead;
+
+use aoc_common::diff::Variant;
+use aoc_common::{debug, input, trace, solver, Answer, Args, Error, Int, ParseError, Puzzle, Rng};
+
+pub mod gen;
+
+const USAGE: &str = " [OPTIONS] FILENAME
+
+Determine the optimal crab alignment.
+
+The input in FILENAME is a comma-separated list of horizontal position values.
+Determine the horizontal position that all crabs could travel to using the
+least fuel.
+
+OPTIONS:
+ -h: Print this usage message and exit
+
+Use - as FILENAME to read from standard input.
+
+https://adventofcode.com/2021/day/7
+";
+
+pub struct Day;
+
+impl Puzzle for Day {
+    const DAY: u32 = 7;
+    const NAME: &'static str = "The Treachery of Whales";
+    const USAGE: &'static str = USAGE;
+    const GEN_USAGE: &'static str = gen::USAGE;
+    const GEN_OPTIONS: &'static [&'static str] = &["--max-position"];
+
+    type Input = Vec<i64>;
+
+    fn parse(&self, text: &str, _args: &Args) -> Result<Vec<i64>, Error> {
+        parse_positions(text)
+    }
+
+    fn part1(&self, positions: &Vec<i64>) -> Answer {
+        let (pos, cost) = find_optimal_position(positions, compute_cost_simple);
+        Answer::new("Simple fuel usage", cost).with("position", pos)
+    }
+
+    fn part2(&self, positions: &Vec<i64>) -> Answer {
+        let (pos, cost) = find_optimal_position(positions, compute_cost_dynamic);
+        Answer::new("Dynamic fuel usage", cost).with("position", pos)
+    }
+
+    fn generate(&self, rng: &mut Rng, args: &Args) -> Result<String, Error> {
+        let count = solver::gen_size(args, 1000)?;
+        let max_position = args.parsed_value("--max-position")?.unwrap_or(1999);
+        Ok(gen::positions(rng, count, max_position))
+    }
+
+    fn variants(&self) -> Vec<Variant<Vec<i64>>> {
+        vec![
+            Variant::new("median", 1, |positions| {
+                let (pos, cost) = optimal_position_median(positions);
+                Answer::new("Simple fuel usage", cost).with("position", pos)
+            }),
+            Variant::new("mean", 2, |positions| {
+                let (pos, cost) = optimal_position_mean(positions);
+                Answer::new("Dynamic fuel usage", cost).with("position", pos)
+            }),
+        ]
+    }
+}
+
+pub fn load_positions<R: BufRead>(reader: R) -> Result<Vec<i64>, Error> {
+    parse_positions(&input::read_all(reader)?)
+}
+
+/// Parse a comma-separated list of positions.
+pub fn parse_positions(data: &str) -> Result<Vec<i64>, Error> {
+    let mut positions = Vec::new();
+    for value in data.trim().split(',') {
+        let pos = value.parse().map_err(|_| {
+            ParseError::in_text(data, value, &format!("expected a position, found {:?}", value))
+        })?;
+        positions.push(pos);
+    }
+    Ok(positions)
+}
+
+/// Find the position that all crabs could travel to using the least fuel, as
+/// measured by `compute_cost`. Returns the position and its fuel cost.
+pub fn find_optimal_position(positions: &[i64], compute_cost: fn(&[i64], i64) -> Int) -> (i64, Int) {
+    let min_pos = *positions.iter().min().unwrap();
+    let max_pos = *positions.iter().max().unwrap();
+
+    let mut optimal_pos = min_pos;
+    let mut cur_cost = None;
+
+    // simple brute-force approach... this feels like the kind of problem where
+    // it's meant to fit some well-known algorithm, but idk what it is and this
+    // doesn't really take that long to run.
+    for pos in min_pos..=max_pos {
+        let cost = compute_cost(positions, pos);
+        trace!("position {} costs {}", pos, cost);
+        if cur_cost.as_ref().is_none_or(|cur_cost| cost < *cur_cost) {
+            debug!("position {} costs {}, the least so far", pos, cost);
+            cur_cost = Some(cost);
+            optimal_pos = pos;
+        }
+    }
+    (optimal_pos, cur_cost.unwrap())
+}
+
+/// Like `find_optimal_position` with `compute_cost_simple`, but goes straight
+/// to the (lower) median, which is the lowest position with the least cost.
+pub fn optimal_position_median(positions: &[i64]) -> (i64, Int) {
+    let mut sorted = positions.to_vec();
+    sorted.sort_unstable();
+    let pos = sorted[(sorted.len() - 1) / 2];
+    (pos, compute_cost_simple(positions, pos))
+}
+
+/// Like `find_optimal_position` with `compute_cost_dynamic`, but only tries
+/// the positions around the mean. The dynamic cost is lowest within half a
+/// step of the mean, so the best whole position is next to it.
+pub fn optimal_position_mean(positions: &[i64]) -> (i64, Int) {
+    // an i128 holds the sum of any number of i64s that fit in memory
+    let sum = positions.iter().map(|&pos| i128::from(pos)).sum::<i128>();
+    let mean = sum.div_euclid(positions.len() as i128) as i64;
+    (mean.saturating_sub(1)..=mean.saturating_add(2))
+        .map(|pos| (pos, compute_cost_dynamic(positions, pos)))
+        .min_by(|(pos, cost), (other_pos, other_cost)| cost.cmp(other_cost).then(pos.cmp(other_pos)))
+        .unwrap()
+}
+
+pub fn compute_cost_simple(positions: &[i64], origin: i64) -> Int {
+    let mut cost = Int::default();
+    for pos in positions {
+        cost += pos.abs_diff(origin);
+    }
+    cost
+}
+
+pub fn compute_cost_dynamic(positions: &[i64], origin: i64) -> Int {
+    let mut cost = Int::default();
+    for pos in positions {
+        cost += distance_to_fuel(pos.abs_diff(origin));
+    }
+    cost
+}
+
+/// The fuel to move `distance` steps, where each step costs one more than the
+/// last. Halving whichever factor is even keeps this within a u128.
+pub fn distance_to_fuel(distance: u64) -> u128 {
+    let (distance, next) = (u128::from(distance), u128::from(distance) + 1);
+    if distance % 2 == 0 { distance / 2 * next } else { distance * (next / 2) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = include_str!("example.txt");
+
+    #[test]
+    fn simple_cost() {
+        let positions = parse_positions(EXAMPLE).unwrap();
+        assert_eq!(compute_cost_simple(&positions, 2), 37);
+        assert_eq!(compute_cost_simple(&positions, 10), 71);
+        assert_eq!(find_optimal_position(&positions, compute_cost_simple), (2, Int::from(37)));
+    }
+
+    #[test]
+    fn dynamic_cost() {
+        let positions = parse_positions(EXAMPLE).unwrap();
+        assert_eq!(distance_to_fuel(11), 66);
+        assert_eq!(compute_cost_dynamic(&positions, 2), 206);
+        assert_eq!(find_optimal_position(&positions, compute_cost_dynamic), (5, Int::from(168)));
+    }
+
+    #[test]
+    fn far_apart_positions() {
+        let positions = [i64::MIN, i64::MAX];
+        assert_eq!(compute_cost_simple(&positions, 0).to_string(), "18446744073709551615");
+        assert_eq!(distance_to_fuel(u64::MAX), u128::from(u64::MAX) * (u128::from(u64::MAX) + 1) / 2);
+        assert_eq!(optimal_position_mean(&positions).1, compute_cost_dynamic(&positions, -1));
+    }
+
+    #[test]
+    fn variants_match_brute_force() {
+        let mut rng = Rng::new(7);
+        for _ in 0..50 {
+            let positions = parse_positions(&gen::positions(&mut rng, 15, 40)).unwrap();
+            assert_eq!(optimal_position_median(&positions), find_optimal_position(&positions, compute_cost_simple));
+            assert_eq!(optimal_position_mean(&positions), find_optimal_position(&positions, compute_cost_dynamic));
+        }
+    }
+
+    #[test]
+    fn rejects_empty_input() {
+        assert!(parse_positions("").is_err());
+    }
+
+    #[test]
+    fn generated_positions_parse() {
+        let positions = parse_positions(&gen::positions(&mut Rng::new(1), 100, 50)).unwrap();
+        assert_eq!(positions.len(), 100);
+        assert!(positions.iter().all(|&pos| (0..=50).contains(&pos)));
+    }
+}
@@ -0,0 +1,40 @@
+use aoc_common::Rng;
+
+pub const USAGE: &str = " [OPTIONS]
+
+Generate random seven-segment displays, each with its own scrambled wiring.
+SIZE is the number of displays (200 by default).
+
+OPTIONS:
+ -h: Print this usage message and exit
+";
+
+/// The segments lit for each digit, with the wires connected correctly.
+const DIGITS: [&str; 10] = ["abcefg", "cf", "acdeg", "acdfg", "bcdf", "abdfg", "abdefg", "acf", "abcdefg", "abcdfg"];
+
+/// The pattern for `digit` with the wires crossed according to `wiring`, in
+/// a random order.
+fn scramble(rng: &mut Rng, wiring: &[char], digit: usize) -> String {
+    let mut pattern: Vec<char> = DIGITS[digit].chars().map(|c| wiring[(c as u8 - b'a') as usize]).collect();
+    rng.shuffle(&mut pattern);
+    pattern.into_iter().collect()
+}
+
+/// Generate `count` displays. Each line has all ten patterns in a random
+/// order and a random four-digit readout, all using the same random wiring.
+pub fn displays(rng: &mut Rng, count: usize) -> String {
+    let mut text = String::new();
+    for _ in 0..count {
+        let mut wiring: Vec<char> = "abcdefg".chars().collect();
+        rng.shuffle(&mut wiring);
+        let mut digits: Vec<usize> = (0..10).collect();
+        rng.shuffle(&mut digits);
+        let patterns: Vec<String> = digits.iter().map(|&digit| scramble(rng, &wiring, digit)).collect();
+        let readout: Vec<String> = (0..4).map(|_| {
+            let digit = rng.index(10);
+            scramble(rng, &wiring, digit)
+        }).collect();
+        text += &format!("{} | {}\n", patterns.join(" "), readout.join(" "));
+    }
+    text
+}
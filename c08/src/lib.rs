@@ -0,0 +1,468 @@
+use std::io::BufRead;
+
+use aoc_common::render::{Color, Picture};
+use aoc_common::{debug, info, input, solver, Answer, Args, Error, ParseError, Puzzle, Rng};
+
+pub mod gen;
+
+const USAGE: &str = " [OPTIONS] FILENAME
+
+Unscramble seven-segment digits.
+
+Each line in FILENAME corresponds to a 4-digit 7-segment display. For each
+display, there are ten signal patterns. Each represents an individual digit.
+The order of the characters in the pattern is not significant. The latter part
+of each line is four patterns that represent the final value of that display.
+
+Determine the final value for each display, based on the fact that some digits
+have a unique number of segments.
+
+OPTIONS:
+ --render PATH: Draw each display's decoded readout as seven-segment digits,
+                with ones, fours, sevens and eights in their own color (see
+                RENDERING below)
+ -h: Print this usage message and exit
+
+Use - as FILENAME to read from standard input.
+
+https://adventofcode.com/2021/day/8
+";
+
+pub struct Day;
+
+impl Puzzle for Day {
+    const DAY: u32 = 8;
+    const NAME: &'static str = "Seven Segment Search";
+    const USAGE: &'static str = USAGE;
+    const OPTIONS: &'static [&'static str] = &["--render"];
+    const GEN_USAGE: &'static str = gen::USAGE;
+
+    type Input = Vec<Display>;
+
+    fn parse(&self, text: &str, _args: &Args) -> Result<Vec<Display>, Error> {
+        load_displays(text.as_bytes())
+    }
+
+    fn part1(&self, displays: &Vec<Display>) -> Answer {
+        Answer::new("Ones, fours, sevens, and eights", count_simple_digits(displays))
+    }
+
+    fn part2(&self, displays: &Vec<Display>) -> Answer {
+        Answer::new("Sum", sum_values(displays))
+    }
+
+    fn generate(&self, rng: &mut Rng, args: &Args) -> Result<String, Error> {
+        Ok(gen::displays(rng, solver::gen_size(args, 200)?))
+    }
+
+    fn render(&self, displays: &Vec<Display>, _args: &Args) -> Result<Option<Picture>, Error> {
+        Ok(Some(render_displays(displays)))
+    }
+}
+
+/// Read displays, one per line. Pass `text.as_bytes()` to read from a string.
+pub fn load_displays<R: BufRead>(reader: R) -> Result<Vec<Display>, Error> {
+    let mut displays = Vec::new();
+    for (idx, line) in input::lines(reader)?.iter().enumerate() {
+        info!("line {}: {}", idx + 1, line);
+        displays.push(Display::from_string(line).map_err(|err| err.on_line(idx + 1))?);
+    }
+    Ok(displays)
+}
+
+/// Count the ones, fours, sevens and eights in the displays' readouts.
+pub fn count_simple_digits(displays: &[Display]) -> usize {
+    let mut simple_digits_count = 0;
+    for display in displays {
+        for digit in display.readout {
+            simple_digits_count += match digit {
+                1 => 1,
+                4 => 1,
+                7 => 1,
+                8 => 1,
+                _ => 0,
+            };
+        }
+    }
+    simple_digits_count
+}
+
+pub fn sum_values(displays: &[Display]) -> i32 {
+    displays.iter().map(|display| display.value()).sum()
+}
+
+/// The segments lit for each digit, as bits numbered the same way as
+/// `Display::segment_map`.
+const DIGIT_SEGMENTS: [u8; 10] = [
+    0b1110111, 0b0100100, 0b1011101, 0b1101101, 0b0101110,
+    0b1101011, 0b1111011, 0b0100101, 0b1111111, 0b1101111,
+];
+
+/// Where each segment is drawn within a digit's 3x3 cells, and with what.
+const SEGMENT_CELLS: [(usize, usize, char); 7] = [
+    (1, 0, '_'), (0, 1, '|'), (2, 1, '|'), (1, 1, '_'), (0, 2, '|'), (2, 2, '|'), (1, 2, '_'),
+];
+
+/// Displays per row of a picture.
+const DISPLAYS_ACROSS: usize = 6;
+
+/// Draw each display's readout as seven-segment digits, several displays to
+/// a row. The digits counted in part 1 are cyan and the rest are orange.
+pub fn render_displays(displays: &[Display]) -> Picture {
+    // each digit takes 3 cells and a space, and each display 4 digits
+    let (pitch_x, pitch_y) = (4 * 4 + 2, 4);
+    let rows = displays.len().div_ceil(DISPLAYS_ACROSS);
+    let across = displays.len().min(DISPLAYS_ACROSS);
+    let mut picture = Picture::new((across * pitch_x).saturating_sub(3), (rows * pitch_y).saturating_sub(1));
+    for (idx, display) in displays.iter().enumerate() {
+        let (left, top) = (idx % DISPLAYS_ACROSS * pitch_x, idx / DISPLAYS_ACROSS * pitch_y);
+        for (pos, &digit) in display.readout.iter().enumerate() {
+            let color = if matches!(digit, 1 | 4 | 7 | 8) { Color::CYAN } else { Color::ORANGE };
+            for (segment, &(x, y, glyph)) in SEGMENT_CELLS.iter().enumerate() {
+                if DIGIT_SEGMENTS[digit as usize] & (1 << segment) != 0 {
+                    picture.set(left + pos * 4 + x, top + y, glyph, color);
+                }
+            }
+        }
+    }
+    picture
+}
+
+fn get_sorted_pattern(pattern: &str) -> String {
+    let mut sorted = pattern.chars().collect::<Vec<char>>();
+    sorted.sort_unstable();
+    String::from_iter(sorted)
+}
+
+fn nth_char(s: &str, n: usize) -> char {
+    s.chars().nth(n).unwrap()
+}
+
+/// Check that a list of patterns has the expected length and that every
+/// pattern is a set of distinct segments from a through g.
+fn check_patterns(line: &str, text: &str, patterns: &[&str], expected: usize, what: &str) -> Result<(), ParseError> {
+    if patterns.len() != expected {
+        let message = format!("expected {} {}, found {}", expected, what, patterns.len());
+        return Err(ParseError::at_token(1, line, text, &message));
+    }
+    for pattern in patterns {
+        let mut seen = Vec::new();
+        for c in pattern.chars() {
+            if !('a'..='g').contains(&c) || seen.contains(&c) {
+                return Err(ParseError::at_token(1, line, pattern, "expected distinct segments a through g"));
+            }
+            seen.push(c);
+        }
+    }
+    Ok(())
+}
+
+fn is_signal_subset(s: &str, p: &str) -> bool {
+    for c in p.chars() {
+        if !s.contains(c) { return false; }
+    }
+    true
+}
+
+pub struct Display {
+    pub one: String,
+    pub four: String,
+    pub seven: String,
+    pub eight: String,
+    pub three: String,
+    pub six: String,
+    pub nine: String,
+    pub zero: String,
+    pub two: String,
+    pub five: String,
+
+    /*
+    Segment map is laid out as follows:
+     0000
+    1    2
+    1    2
+     3333
+    4    5
+    4    5
+     6666
+    */
+    pub segment_map: [char; 7],
+    pub readout: [i32; 4],
+}
+
+const SEGMENTS_IN_ONE: usize = 2;
+const SEGMENTS_IN_FOUR: usize = 4;
+const SEGMENTS_IN_SEVEN: usize = 3;
+const SEGMENTS_IN_EIGHT: usize = 7;
+
+impl Display {
+    pub fn value(&self) -> i32 {
+        self.readout[0] * 1000
+            + self.readout[1] * 100
+            + self.readout[2] * 10
+            + self.readout[3]
+    }
+    // all this seems a little clunky but ¯\_(ツ)_/¯
+    // Errors are reported as being on line 1; callers parsing a whole file
+    // should fix that up with ParseError::on_line.
+    pub fn from_string(s: &str) -> Result<Display, ParseError> {
+        let (patterns_text, digits_text) = s.split_once('|')
+            .ok_or_else(|| ParseError::at_end(1, s, "expected '|' between the signal patterns and the readout"))?;
+        let patterns: Vec<&str> = patterns_text.split_whitespace().collect();
+        let digits: Vec<&str> = digits_text.split_whitespace().collect();
+        check_patterns(s, patterns_text.trim(), &patterns, 10, "signal patterns")?;
+        check_patterns(s, digits_text.trim(), &digits, 4, "readout patterns")?;
+
+        // the deduction below relies on there being one pattern per digit
+        let mut length_counts = [0; 8];
+        for pattern in &patterns {
+            length_counts[pattern.len()] += 1;
+        }
+        if length_counts != [0, 0, 1, 1, 1, 3, 3, 1] {
+            return Err(ParseError::at_token(1, s, patterns_text.trim(), "expected one signal pattern for each digit"));
+        }
+
+        let mut display = Display {
+            one: "".to_string(),
+            four: "".to_string(),
+            seven: "".to_string(),
+            eight: "".to_string(),
+            three: "".to_string(),
+            six: "".to_string(),
+            nine: "".to_string(),
+            zero: "".to_string(),
+            two: "".to_string(),
+            five: "".to_string(),
+            segment_map: ['\0', '\0', '\0', '\0', '\0', '\0', '\0'],
+            readout: [0xff, 0xff, 0xff, 0xff],
+        };
+
+        // first pass - get unambiguous digits
+        for pattern in &patterns {
+            let sorted = get_sorted_pattern(pattern);
+            match sorted.len() {
+                SEGMENTS_IN_ONE => {
+                    display.one = sorted;
+                },
+                SEGMENTS_IN_FOUR => {
+                    display.four = sorted;
+                },
+                SEGMENTS_IN_SEVEN => {
+                    display.seven = sorted;
+                },
+                SEGMENTS_IN_EIGHT => {
+                    display.eight = sorted;
+                },
+                _ => (),
+            };
+        }
+        debug!("first pass: one is {}, four is {}, seven is {}, eight is {}", display.one, display.four, display.seven, display.eight);
+
+        // we know segment 0 because it is the difference between a one and a seven
+        display.segment_map[0] = nth_char(
+            &display.seven.replace([nth_char(&display.one, 0), nth_char(&display.one, 1)], ""),
+            0);
+        debug!("segment 0 is {:?}, the one in seven but not in one", display.segment_map[0]);
+
+        // second pass - find three
+        for pattern in &patterns {
+            let sorted = get_sorted_pattern(pattern);
+            if sorted.len() == 5 && is_signal_subset(&sorted, &display.seven) {
+                // three is a 5-segment pattern that contains the 3 segments from seven
+                display.three = sorted;
+                break;
+            }
+        }
+
+        if display.three.is_empty() {
+            return Err(ParseError::at_token(1, s, patterns_text.trim(), "signal patterns don't form a consistent set of digits"));
+        }
+
+        // segments 1 and 3 are the difference between four and one
+        // now we can disambiguate them because we've identified three
+        let four_ambigs = display.four
+            .replace([nth_char(&display.one, 0), nth_char(&display.one, 1)], "");
+
+        let four_ambig_0 = nth_char(&four_ambigs, 0);
+        let four_ambig_1 = nth_char(&four_ambigs, 1);
+        if display.three.contains(four_ambig_0) {
+            display.segment_map[3] = four_ambig_0;
+            display.segment_map[1] = four_ambig_1;
+        } else {
+            display.segment_map[1] = four_ambig_0;
+            display.segment_map[3] = four_ambig_1;
+        }
+        debug!("second pass: three is {}, so segment 1 is {:?} and segment 3 is {:?}",
+               display.three, display.segment_map[1], display.segment_map[3]);
+
+        // third pass - find six
+        for pattern in &patterns {
+            let sorted = get_sorted_pattern(pattern);
+            if sorted.len() == 6 && sorted.replace([nth_char(&display.one, 0), nth_char(&display.one, 1)], "").len() == 5 {
+                // six is a 6-segment pattern that shares 1 segment with one
+                display.six = sorted;
+                break;
+            }
+        }
+        debug!("third pass: six is {}", display.six);
+
+        // fourth pass - find nine
+        for pattern in &patterns {
+            let sorted = get_sorted_pattern(pattern);
+            if sorted.len() == 6 && sorted != display.six && sorted.contains(display.segment_map[3]) {
+                // nine is the only other 6-segment pattern that contains segment 3 (the middle)
+                display.nine = sorted;
+                break;
+            }
+        }
+        debug!("fourth pass: nine is {}", display.nine);
+
+        // fifth pass - find zero
+        for pattern in &patterns {
+            let sorted = get_sorted_pattern(pattern);
+            if sorted.len() == 6 && sorted != display.six && sorted != display.nine {
+                // zero is the only other 6-segment pattern
+                display.zero = sorted;
+            }
+        }
+        debug!("fifth pass: zero is {}", display.zero);
+
+        if display.six.contains(nth_char(&display.one, 0)) {
+            display.segment_map[5] = nth_char(&display.one, 0);
+            display.segment_map[2] = nth_char(&display.one, 1);
+        } else {
+            display.segment_map[2] = nth_char(&display.one, 0);
+            display.segment_map[5] = nth_char(&display.one, 1);
+        }
+        debug!("segment 2 is {:?} and segment 5 is {:?}, going by which of one's segments six has",
+               display.segment_map[2], display.segment_map[5]);
+
+        display.segment_map[6] = nth_char(&display.three
+            .replace([
+                display.segment_map[0],
+                display.segment_map[2],
+                display.segment_map[3],
+                display.segment_map[5],
+            ], ""), 0);
+        debug!("segment 6 is {:?}, the one left in three", display.segment_map[6]);
+
+        display.segment_map[4] = nth_char(&display.eight
+            .replace([
+                display.segment_map[0],
+                display.segment_map[1],
+                display.segment_map[2],
+                display.segment_map[3],
+                display.segment_map[5],
+                display.segment_map[6],
+            ], ""), 0);
+        debug!("segment 4 is {:?}, the one left in eight", display.segment_map[4]);
+
+        display.two = get_sorted_pattern(&String::from_iter([
+            display.segment_map[0],
+            display.segment_map[2],
+            display.segment_map[3],
+            display.segment_map[4],
+            display.segment_map[6]
+        ]));
+
+        display.five = get_sorted_pattern(&String::from_iter([
+            display.segment_map[0],
+            display.segment_map[1],
+            display.segment_map[3],
+            display.segment_map[5],
+            display.segment_map[6]
+        ]));
+
+        for (i, &digit_pattern) in digits.iter().enumerate() {
+            let mut sorted = digit_pattern.chars().collect::<Vec<char>>();
+            sorted.sort_unstable();
+            let sorted = String::from_iter(sorted);
+            display.readout[i] = if sorted == display.one {
+                1
+            } else if sorted == display.four {
+                4
+            } else if sorted == display.seven {
+                7
+            } else if sorted == display.eight {
+                8
+            } else if sorted == display.three {
+                3
+            } else if sorted == display.six {
+                6
+            } else if sorted == display.nine{
+                9
+            } else if sorted == display.zero {
+                0
+            } else if sorted == display.two {
+                2
+            } else if sorted == display.five {
+                5
+            } else {
+                return Err(ParseError::at_token(1, s, digit_pattern, "readout pattern doesn't match any signal pattern"));
+            };
+        }
+
+        Ok(display)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = include_str!("example.txt");
+
+    #[test]
+    fn from_string_deduces_segments() {
+        let line = "acedgfb cdfbe gcdfa fbcad dab cefabd cdfgeb eafb cagedb ab | cdfeb fcadb cdfeb cdbaf";
+        let display = Display::from_string(line).unwrap();
+        assert_eq!(display.segment_map, ['d', 'e', 'a', 'f', 'g', 'b', 'c']);
+        assert_eq!(display.readout, [5, 3, 5, 3]);
+        assert_eq!(display.value(), 5353);
+    }
+
+    #[test]
+    fn simple_digits() {
+        let displays = load_displays(EXAMPLE.as_bytes()).unwrap();
+        assert_eq!(count_simple_digits(&displays), 26);
+    }
+
+    #[test]
+    fn sum() {
+        let displays = load_displays(EXAMPLE.as_bytes()).unwrap();
+        assert_eq!(displays[0].value(), 8394);
+        assert_eq!(sum_values(&displays), 61229);
+    }
+
+    #[test]
+    fn renders_seven_segment_digits() {
+        let line = "acedgfb cdfbe gcdfa fbcad dab cefabd cdfgeb eafb cagedb ab | cdfeb fcadb cdfeb cdbaf";
+        let mut display = Display::from_string(line).unwrap();
+        display.readout = [0, 1, 4, 7];
+        assert_eq!(render_displays(&[display]).to_ascii(), " _           _\n| |   | |_|   |\n|_|   |   |   |\n");
+        let displays = load_displays(EXAMPLE.as_bytes()).unwrap();
+        let picture = render_displays(&displays);
+        assert_eq!((picture.width(), picture.height()), (6 * 18 - 3, 2 * 4 - 1));
+    }
+
+    #[test]
+    fn rejects_missing_separator() {
+        let err = Display::from_string("ab cd").err().unwrap();
+        assert_eq!((err.line, err.column), (1, 6));
+    }
+
+    #[test]
+    fn reports_line_of_bad_display() {
+        match load_displays("acedgfb cdfbe gcdfa fbcad dab cefabd cdfgeb eafb cagedb ab | cdfeb fcadb cdfeb cdbaf\nab | cd\n".as_bytes()) {
+            Err(Error::Parse(err)) => assert_eq!(err.line, 2),
+            other => panic!("expected a parse error, got {:?}", other.err()),
+        }
+    }
+
+    #[test]
+    fn generated_displays_decode() {
+        let displays = load_displays(gen::displays(&mut Rng::new(1), 20).as_bytes()).unwrap();
+        assert_eq!(displays.len(), 20);
+        sum_values(&displays);
+    }
+}
@@ -0,0 +1,70 @@
+use std::collections::{HashSet, VecDeque};
+
+use aoc_common::Rng;
+
+pub const USAGE: &str = " [OPTIONS]
+
+Generate a random heightmap. SIZE is the number of rows (100 by default).
+
+OPTIONS:
+ --width WIDTH: Number of columns (the same as SIZE by default)
+ --wall-percent PERCENT: Share of the locations with height 9, which separate
+                         the basins (default 30)
+ -h: Print this usage message and exit
+";
+
+/// Generate a `width` by `height` heightmap where `wall_percent` percent of
+/// the locations are 9. The walls split the rest into regions, and each
+/// region is one basin: a random low point of height 0, with the height
+/// rising by one for each step away from it (up to 8), so that every other
+/// location flows down to it.
+pub fn heightmap(rng: &mut Rng, width: usize, height: usize, wall_percent: u32) -> String {
+    let walls: Vec<bool> = (0..width * height).map(|_| rng.below(100) < u64::from(wall_percent)).collect();
+    let neighbours = |idx: usize| {
+        let (x, y) = (idx % width, idx / width);
+        [
+            (x > 0).then(|| idx - 1),
+            (x + 1 < width).then(|| idx + 1),
+            (y > 0).then(|| idx - width),
+            (y + 1 < height).then(|| idx + width),
+        ].into_iter().flatten().filter(|&idx| !walls[idx])
+    };
+    let mut heights: Vec<Option<u8>> = walls.iter().map(|&wall| wall.then_some(9)).collect();
+    for start in 0..heights.len() {
+        if heights[start].is_some() {
+            continue;
+        }
+        // Find the whole region first, so the low point can be anywhere in it
+        let mut region = vec![start];
+        let mut seen = HashSet::from([start]);
+        let mut next = 0;
+        while next < region.len() {
+            for idx in neighbours(region[next]) {
+                if seen.insert(idx) {
+                    region.push(idx);
+                }
+            }
+            next += 1;
+        }
+        let low_point = region[rng.below(region.len() as u64) as usize];
+        heights[low_point] = Some(0);
+        let mut queue = VecDeque::from([low_point]);
+        while let Some(idx) = queue.pop_front() {
+            let step = heights[idx].unwrap().saturating_add(1).min(8);
+            for neighbour in neighbours(idx) {
+                if heights[neighbour].is_none() {
+                    heights[neighbour] = Some(step);
+                    queue.push_back(neighbour);
+                }
+            }
+        }
+    }
+    let mut text = String::new();
+    for row in heights.chunks(width.max(1)) {
+        for &value in row {
+            text += &value.unwrap().to_string();
+        }
+        text += "\n";
+    }
+    text
+}
@@ -0,0 +1,231 @@
+use aoc_common::render::{Color, Picture};
+use aoc_common::{debug, input, solver, Answer, Args, Error, Grid, Puzzle, Rng};
+use std::collections::HashSet;
+use std::io::BufRead;
+use std::str::FromStr;
+
+pub mod gen;
+
+const USAGE: &str = " [OPTIONS] FILENAME
+
+Model smoke flow through caves.
+
+The input in FILENAME is a heightmap of the cave floor, consisting of a grid of
+height values ranging from 0 through 9.
+
+The output consists of two numbers: an \"aggregate risk level of low points\"
+and the product of the sizes of the three largest basins formed by the low
+points.
+
+OPTIONS:
+ --render PATH: Draw the heightmap with its basins, the three largest in their
+                own colors (see RENDERING below)
+ -h: Print this usage message and exit
+
+Use - as FILENAME to read from standard input.
+
+https://adventofcode.com/2021/day/9
+";
+
+pub struct Day;
+
+impl Puzzle for Day {
+    const DAY: u32 = 9;
+    const NAME: &'static str = "Smoke Basin";
+    const USAGE: &'static str = USAGE;
+    const OPTIONS: &'static [&'static str] = &["--render"];
+    const GEN_USAGE: &'static str = gen::USAGE;
+    const GEN_OPTIONS: &'static [&'static str] = &["--width", "--wall-percent"];
+
+    type Input = Heightmap;
+
+    fn parse(&self, text: &str, _args: &Args) -> Result<Heightmap, Error> {
+        text.parse()
+    }
+
+    fn part1(&self, heightmap: &Heightmap) -> Answer {
+        Answer::new("Aggregate risk level of low points", heightmap.get_low_points_aggregate_risk())
+    }
+
+    fn part2(&self, heightmap: &Heightmap) -> Answer {
+        Answer::new("Product of three largest basins", heightmap.get_top_three_basin_sizes_product())
+            .with("basin_sizes", heightmap.get_basin_sizes().into_iter().take(3).collect::<Vec<usize>>())
+    }
+
+    fn generate(&self, rng: &mut Rng, args: &Args) -> Result<String, Error> {
+        let height = solver::gen_size(args, 100)?;
+        let width = args.parsed_value_in("--width", 1..)?.unwrap_or(height);
+        let wall_percent = args.parsed_value_in("--wall-percent", 0..=100)?.unwrap_or(30);
+        Ok(gen::heightmap(rng, width, height, wall_percent))
+    }
+
+    fn render(&self, heightmap: &Heightmap, _args: &Args) -> Result<Option<Picture>, Error> {
+        Ok(Some(heightmap.render()))
+    }
+}
+
+#[derive(Debug)]
+#[derive(Eq, Hash, PartialEq)]
+pub struct Coord(pub usize, pub usize);
+
+pub struct Heightmap {
+    pub heights: Grid<u8>,
+}
+
+impl Heightmap {
+    pub fn from_reader<R: BufRead>(reader: R) -> Result<Heightmap, Error> {
+        Ok(Heightmap {
+            heights: Grid::parse_digits(&input::read_all(reader)?)?,
+        })
+    }
+    pub fn get_low_points(&self) -> Vec<Coord> {
+        self.heights.enumerate()
+            .filter(|&((x, y), &val)| self.heights.neighbors4(x, y).all(|pos| self.heights[pos] > val))
+            .map(|((x, y), _)| Coord(x, y))
+            .collect()
+    }
+    pub fn get_height_at(&self, x: usize, y: usize) -> u8 {
+        self.heights[(x, y)]
+    }
+    pub fn get_risk_level(&self, x: usize, y: usize) -> u8 {
+        self.get_height_at(x, y) + 1
+    }
+    /// Add every location in the basin around x,y to `basin`, spreading out
+    /// until the walls of 9. The locations still to visit are kept on a
+    /// stack of their own, so a basin of any size fits.
+    pub fn explore_basin(&self, x: usize, y: usize, basin: &mut HashSet<Coord>) {
+        let mut to_visit = vec![(x, y)];
+        while let Some((x, y)) = to_visit.pop() {
+            if self.get_height_at(x, y) == 9 || !basin.insert(Coord(x, y)) { continue; }
+            to_visit.extend(self.heights.neighbors4(x, y));
+        }
+    }
+    pub fn get_basin_size_at(&self, x: usize, y: usize) -> usize {
+        let mut basin = HashSet::new();
+        self.explore_basin(x, y, &mut basin);
+        basin.len()
+    }
+    pub fn get_low_points_aggregate_risk(&self) -> usize {
+        self.get_low_points().iter().map(|coord| self.get_risk_level(coord.0, coord.1) as usize).sum()
+    }
+    /// The sizes of the basins around each low point, largest first.
+    pub fn get_basin_sizes(&self) -> Vec<usize> {
+        let mut sizes = self.get_low_points().iter()
+            .map(|coord| {
+                let size = self.get_basin_size_at(coord.0, coord.1);
+                debug!("basin around the low point at {},{} has size {}", coord.0, coord.1, size);
+                size
+            })
+            .collect::<Vec<usize>>();
+        sizes.sort_unstable();
+        sizes.reverse();
+        sizes
+    }
+    /// The product of the three largest basins' sizes, or of all of them if
+    /// there are fewer than three.
+    pub fn get_top_three_basin_sizes_product(&self) -> usize {
+        self.get_basin_sizes().iter().take(3).product()
+    }
+    /// Draw the heights, with walls of 9 greyed out, the low points in
+    /// white, the three largest basins in their own colors and the rest of
+    /// the basins in blue.
+    pub fn render(&self) -> Picture {
+        let low_points = self.get_low_points();
+        let mut basins: Vec<HashSet<Coord>> = low_points.iter()
+            .map(|coord| {
+                let mut basin = HashSet::new();
+                self.explore_basin(coord.0, coord.1, &mut basin);
+                basin
+            })
+            .collect();
+        basins.sort_by_key(|basin| std::cmp::Reverse(basin.len()));
+        let top_colors = [Color::RED, Color::ORANGE, Color::YELLOW];
+
+        let mut picture = Picture::new(self.heights.width(), self.heights.height());
+        for ((x, y), &height) in self.heights.enumerate() {
+            let coord = Coord(x, y);
+            let color = if height == 9 {
+                Color::GREY.dimmed(50)
+            } else if low_points.contains(&coord) {
+                Color::WHITE
+            } else {
+                match basins.iter().position(|basin| basin.contains(&coord)) {
+                    Some(rank) if rank < top_colors.len() => top_colors[rank],
+                    _ => Color::BLUE,
+                }
+            };
+            picture.set(x, y, char::from(b'0' + height), color);
+        }
+        picture
+    }
+}
+
+impl FromStr for Heightmap {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Heightmap, Error> {
+        Heightmap::from_reader(s.as_bytes())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = include_str!("example.txt");
+
+    #[test]
+    fn low_points() {
+        let heightmap: Heightmap = EXAMPLE.parse().unwrap();
+        assert_eq!(heightmap.get_low_points(), vec![Coord(1, 0), Coord(9, 0), Coord(2, 2), Coord(6, 4)]);
+        assert_eq!(heightmap.get_low_points_aggregate_risk(), 15);
+    }
+
+    #[test]
+    fn basins() {
+        let heightmap: Heightmap = EXAMPLE.parse().unwrap();
+        assert_eq!(heightmap.get_basin_size_at(1, 0), 3);
+        assert_eq!(heightmap.get_basin_sizes(), vec![14, 9, 9, 3]);
+        assert_eq!(heightmap.get_top_three_basin_sizes_product(), 1134);
+    }
+
+    #[test]
+    fn renders_largest_basins_in_color() {
+        let heightmap: Heightmap = EXAMPLE.parse().unwrap();
+        let picture = heightmap.render();
+        assert_eq!(picture.to_ascii(), EXAMPLE);
+        assert_eq!(picture.cells[(1, 0)].color, Some(Color::WHITE));
+        assert_eq!(picture.cells[(0, 0)].color, Some(Color::BLUE));
+        assert_eq!(picture.cells[(2, 1)].color, Some(Color::RED));
+    }
+
+    #[test]
+    fn rejects_ragged_rows() {
+        match "123\n12\n".parse::<Heightmap>() {
+            Err(Error::Parse(err)) => assert_eq!(err.line, 2),
+            other => panic!("expected a parse error, got {:?}", other.err()),
+        }
+    }
+
+    #[test]
+    fn generated_heightmap_parses() {
+        let heightmap: Heightmap = gen::heightmap(&mut Rng::new(1), 7, 5, 30).parse().unwrap();
+        assert_eq!((heightmap.heights.height(), heightmap.heights.width()), (5, 7));
+        // Each basin has just one low point, so no location is counted twice
+        let heightmap: Heightmap = gen::heightmap(&mut Rng::new(2), 50, 50, 30).parse().unwrap();
+        let open = heightmap.heights.iter().filter(|&&height| height != 9).count();
+        assert_eq!(heightmap.get_basin_sizes().iter().sum::<usize>(), open);
+    }
+
+    #[test]
+    fn huge_basin_without_walls() {
+        let heightmap: Heightmap = gen::heightmap(&mut Rng::new(3), 300, 300, 0).parse().unwrap();
+        assert_eq!(heightmap.get_basin_sizes(), vec![300 * 300]);
+    }
+
+    #[test]
+    fn fewer_than_three_basins() {
+        let heightmap: Heightmap = "193\n".parse().unwrap();
+        assert_eq!(heightmap.get_top_three_basin_sizes_product(), 1);
+    }
+}
@@ -0,0 +1,64 @@
+use aoc_common::Rng;
+
+pub const USAGE: &str = " [OPTIONS]
+
+Generate random lines of brackets, each either corrupt or incomplete, with an
+odd number of incomplete lines. SIZE is the number of lines (100 by default).
+
+OPTIONS:
+ --max-length LENGTH: Longest line to generate, at least 2 (default 110)
+ --max-open COUNT: Most brackets to leave open at once, at least 1 (default
+                   20)
+ -h: Print this usage message and exit
+";
+
+const PAIRS: [(char, char); 4] = [('(', ')'), ('[', ']'), ('{', '}'), ('<', '>')];
+
+/// Most brackets left open at once, unless --max-open is given. This is
+/// about as many as the puzzle's inputs leave open.
+pub const MAX_OPEN: usize = 20;
+
+/// Generate a line of brackets from 2 to `max_length` characters long, with
+/// at most about `max_open` of them open at once. An incomplete line leaves
+/// some brackets open, while a corrupt line closes one with the wrong bracket
+/// and continues with random brackets after it.
+pub fn line(rng: &mut Rng, max_length: usize, max_open: usize, corrupt: bool) -> String {
+    let length = rng.range(2, max_length as i64) as usize;
+    let mut stack: Vec<char> = Vec::new();
+    let mut text = String::new();
+    while text.len() < length {
+        // open the last two brackets so that there's one left to close
+        // badly, or left open
+        let must_open = stack.is_empty() || text.len() + 2 >= length;
+        if corrupt && text.len() + 1 == length && !stack.is_empty() {
+            let expected = stack.pop().unwrap();
+            let &(_, bad) = PAIRS.iter().filter(|&&(_, closer)| closer != expected).nth(rng.index(3)).unwrap();
+            text.push(bad);
+            let tail = rng.range(0, (max_length - text.len()) as i64);
+            for _ in 0..tail {
+                let &(opener, closer) = rng.choose(&PAIRS);
+                text.push(if rng.chance(0.5) { opener } else { closer });
+            }
+            return text;
+        }
+        if must_open || (stack.len() < max_open && rng.chance(0.55)) {
+            let &(opener, closer) = rng.choose(&PAIRS);
+            text.push(opener);
+            stack.push(closer);
+        } else {
+            text.push(stack.pop().unwrap());
+        }
+    }
+    text
+}
+
+/// Generate `count` lines, roughly half of them corrupt, where an odd number
+/// of them are incomplete so that they have a middle completion score.
+pub fn lines(rng: &mut Rng, count: usize, max_length: usize, max_open: usize) -> String {
+    let mut corrupt: Vec<bool> = (0..count).map(|_| rng.chance(0.5)).collect();
+    if corrupt.iter().filter(|&&c| !c).count() % 2 == 0 {
+        let idx = rng.index(count);
+        corrupt[idx] = !corrupt[idx];
+    }
+    corrupt.into_iter().map(|corrupt| line(rng, max_length, max_open, corrupt) + "\n").collect()
+}
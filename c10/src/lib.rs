@@ -0,0 +1,220 @@
+use std::io::BufRead;
+
+use aoc_common::{debug, input, solver, warn, Answer, Args, Error, Int, ParseError, Puzzle, Rng};
+
+pub mod gen;
+
+const USAGE: &str = " [OPTIONS] FILENAME
+
+Validate syntax of bracket pairs.
+
+The input in FILENAME contains lines of various bracket characters. A line is
+considered corrupt if it has a mismatched bracket pair, while it is incomplete
+if there are unmatched brackets.
+
+OPTIONS:
+ -h: Print this usage message and exit
+
+Use - as FILENAME to read from standard input.
+
+https://adventofcode.com/2021/day/10
+";
+
+pub struct Day;
+
+impl Puzzle for Day {
+    const DAY: u32 = 10;
+    const NAME: &'static str = "Syntax Scoring";
+    const USAGE: &'static str = USAGE;
+    const GEN_USAGE: &'static str = gen::USAGE;
+    const GEN_OPTIONS: &'static [&'static str] = &["--max-length", "--max-open"];
+
+    type Input = Vec<String>;
+
+    fn parse(&self, text: &str, _args: &Args) -> Result<Vec<String>, Error> {
+        load_lines(text.as_bytes())
+    }
+
+    fn part1(&self, lines: &Vec<String>) -> Answer {
+        Answer::new("Total syntax error score", total_syntax_error_score(lines))
+    }
+
+    fn part2(&self, lines: &Vec<String>) -> Answer {
+        let label = "Middle completion score";
+        match middle_completion_score(lines) {
+            Some(score) => Answer::new(label, score),
+            None => Answer::new(label, "none").with("note", "no line is incomplete"),
+        }
+    }
+
+    fn generate(&self, rng: &mut Rng, args: &Args) -> Result<String, Error> {
+        let count = solver::gen_size(args, 100)?;
+        let max_length = args.parsed_value_in("--max-length", 2..)?.unwrap_or(110);
+        let max_open = args.parsed_value_in("--max-open", 1..)?.unwrap_or(gen::MAX_OPEN);
+        Ok(gen::lines(rng, count, max_length, max_open))
+    }
+}
+
+/// Read lines of brackets, checking that they contain nothing else. Pass
+/// `text.as_bytes()` to read from a string.
+pub fn load_lines<R: BufRead>(reader: R) -> Result<Vec<String>, Error> {
+    let lines = input::lines(reader)?;
+    for (idx, line) in lines.iter().enumerate() {
+        if let Some((col, c)) = line.char_indices().find(|&(_, c)| !"()[]{}<>".contains(c)) {
+            let message = format!("expected a bracket, found {:?}", c);
+            return Err(ParseError::at_token(idx + 1, line, &line[col..col + c.len_utf8()], &message).into());
+        }
+    }
+    Ok(lines)
+}
+
+/// Sum the validation scores of every corrupt line.
+pub fn total_syntax_error_score(lines: &[String]) -> Int {
+    lines.iter()
+        .enumerate()
+        .map(|(idx, line)| {
+            let score = score_line(line).0;
+            if score != 0 {
+                debug!("line {} is corrupt, scoring {}", idx + 1, score);
+            }
+            score
+        })
+        .sum()
+}
+
+/// Find the median completion score of the incomplete (but not corrupt)
+/// lines, or `None` if there are none.
+pub fn middle_completion_score(lines: &[String]) -> Option<Int> {
+    let mut completion_scores: Vec<Int> = Vec::new();
+    for (idx, line) in lines.iter().enumerate() {
+        let (validation_score, completion_score) = score_line(line);
+        if validation_score == 0 {
+            // Corrupt lines have nonzero validation score, so ignore them
+            debug!("line {} is incomplete, with a completion score of {}", idx + 1, completion_score);
+            completion_scores.push(completion_score);
+        }
+    }
+    if completion_scores.is_empty() {
+        return None;
+    }
+    completion_scores.sort();
+    Some(completion_scores.swap_remove(completion_scores.len() / 2))
+}
+
+/// Validate a line of brackets. Returns the line's validation score, which is
+/// nonzero if the line is corrupt, and its completion score. The completion
+/// score grows fivefold with each open bracket, so it's an `Int`.
+pub fn score_line(line: &str) -> (u64, Int) {
+    let mut expected_stack: Vec<char> = Vec::new();
+    let mut validation_score: u64 = 0;
+
+    fn validate(expected_stack: &mut Vec<char>, c: char) -> u64 {
+        let expected = expected_stack.pop();
+        if expected.is_none() {
+            warn!("unmatched closer {:?}", c);
+            return 0;
+        }
+        let expected = expected.unwrap();
+        if expected == c {
+            return 0;
+        }
+        debug!("expected {:?}, but found {:?} instead", expected, c);
+        match c {
+            ')' => 3,
+            ']' => 57,
+            '}' => 1197,
+            '>' => 25137,
+            unknown => panic!("Unknown character '{:?}'", unknown),
+        }
+    }
+
+    fn expect(expected_stack: &mut Vec<char>, c: char) -> u64 {
+        expected_stack.push(c);
+        0
+    }
+
+    for c in line.chars() {
+        let score = match c {
+            '(' => expect(&mut expected_stack, ')'),
+            ')' => validate(&mut expected_stack, c),
+            '[' => expect(&mut expected_stack, ']'),
+            ']' => validate(&mut expected_stack, c),
+            '{' => expect(&mut expected_stack, '}'),
+            '}' => validate(&mut expected_stack, c),
+            '<' => expect(&mut expected_stack, '>'),
+            '>' => validate(&mut expected_stack, c),
+            unknown => panic!("Unknown character '{:?}'", unknown),
+        };
+        validation_score += score;
+        if score > 0 {
+            break;
+        }
+    }
+
+    let mut completion_score = Int::default();
+    // Handle incomplete lines
+    while let Some(c) = expected_stack.pop() {
+        completion_score = completion_score * 5 + match c {
+            ')' => 1,
+            ']' => 2,
+            '}' => 3,
+            '>' => 4,
+            unknown => panic!("Bad completion stack value '{:?}'", unknown),
+        }
+    }
+
+    (validation_score, completion_score)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = include_str!("example.txt");
+
+    #[test]
+    fn score_corrupt_line() {
+        assert_eq!(score_line("{([(<{}[<>[]}>{[]{[(<()>").0, 1197);
+        assert_eq!(score_line("[[<[([]))<([[{}[[()]]]").0, 3);
+    }
+
+    #[test]
+    fn score_incomplete_line() {
+        assert_eq!(score_line("[({(<(())[]>[[{[]{<()<>>"), (0, Int::from(288957)));
+        assert_eq!(score_line("<{([{{}}[<[[[<>{}]]]>[]]"), (0, Int::from(294)));
+    }
+
+    #[test]
+    fn score_deeply_incomplete_line() {
+        assert_eq!(score_line(&"<".repeat(40)).1.to_string(), "9094947017729282379150390624");
+    }
+
+    #[test]
+    fn totals() {
+        let lines = load_lines(EXAMPLE.as_bytes()).unwrap();
+        assert_eq!(total_syntax_error_score(&lines), 26397);
+        assert_eq!(middle_completion_score(&lines), Some(Int::from(288957)));
+    }
+
+    #[test]
+    fn no_incomplete_lines() {
+        let lines = load_lines("(]\n{()()()>\n".as_bytes()).unwrap();
+        assert_eq!(total_syntax_error_score(&lines), 57 + 25137);
+        assert_eq!(middle_completion_score(&lines), None);
+        assert_eq!(middle_completion_score(&[]), None);
+        assert_eq!(Day.part2(&lines).value, "none".into());
+    }
+
+    #[test]
+    fn rejects_non_bracket() {
+        assert!(load_lines("(a)\n".as_bytes()).is_err());
+    }
+
+    #[test]
+    fn generated_lines_are_corrupt_or_incomplete() {
+        let lines = load_lines(gen::lines(&mut Rng::new(1), 51, 40, gen::MAX_OPEN).as_bytes()).unwrap();
+        let incomplete = lines.iter().filter(|line| score_line(line).0 == 0).count();
+        assert_eq!(incomplete % 2, 1);
+        assert!(lines.iter().all(|line| score_line(line) != (0, Int::default())));
+    }
+}
@@ -0,0 +1,179 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::{Answer, Error, Format, Json, ParseError};
+
+/// Where answers are recorded unless --answers says otherwise.
+pub const DEFAULT_DIR: &str = "answers";
+
+/// A 64-bit FNV-1a hash of the input. Unlike std's hashers, it's guaranteed
+/// to stay the same between builds, so it can name files.
+pub fn hash(text: &str) -> u64 {
+    text.bytes().fold(0xcbf29ce484222325, |hash, byte| (hash ^ u64::from(byte)).wrapping_mul(0x100000001b3))
+}
+
+/// The outcome of checking one part's answer against the recorded one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Verdict {
+    Pass,
+    /// The answer differs from the recorded one, which is given.
+    Fail(String),
+    /// Nothing has been recorded for the part.
+    Missing,
+}
+
+impl Verdict {
+    pub fn passed(&self) -> bool {
+        *self == Verdict::Pass
+    }
+
+    /// The JSON form of a part's verdict: `day`, `part`, `status` (`pass`,
+    /// `fail` or `missing`), `answer` and the `expected` answer, if any.
+    pub fn to_json(&self, day: u32, part: u32, answer: &Answer) -> Json {
+        let (status, expected) = match self {
+            Verdict::Pass => ("pass", answer.value.clone()),
+            // recorded answers are already serialized, so pass them through
+            // verbatim
+            Verdict::Fail(expected) => ("fail", Json::Number(expected.clone())),
+            Verdict::Missing => ("missing", Json::Null),
+        };
+        Json::object(vec![
+            ("day", day.into()),
+            ("part", part.into()),
+            ("status", status.into()),
+            ("answer", answer.value.clone()),
+            ("expected", expected),
+        ])
+    }
+}
+
+/// Print one part's verdict in the given format.
+pub fn print_verdict(format: Format, day: u32, part: u32, answer: &Answer, verdict: &Verdict) {
+    match format {
+        Format::Text => match verdict {
+            Verdict::Pass => println!("Part {}: PASS ({})", part, answer),
+            Verdict::Fail(expected) => println!("Part {}: FAIL ({}, expected {})", part, answer, expected),
+            Verdict::Missing => println!("Part {}: FAIL ({}, no recorded answer)", part, answer),
+        },
+        Format::Json => println!("{}", verdict.to_json(day, part, answer)),
+    }
+}
+
+/// The recorded answers for one day's input, stored in a small text file
+/// named after the day and a hash of the input, plus a hash of any settings
+/// that change the answers. Each line holds a part number and that part's
+/// answer as JSON, e.g. `2: 21577`.
+#[derive(Debug, Clone)]
+pub struct KnownAnswers {
+    pub path: PathBuf,
+    pub answers: BTreeMap<u32, String>,
+}
+
+impl KnownAnswers {
+    /// The path of the answers file for the given day and input, solved
+    /// with `settings` (see `solver::answer_settings`). The puzzle's own
+    /// answers have no settings.
+    pub fn path_for(dir: &Path, day: u32, text: &str, settings: &str) -> PathBuf {
+        if settings.is_empty() {
+            dir.join(format!("day{:02}-{:016x}.txt", day, hash(text)))
+        } else {
+            dir.join(format!("day{:02}-{:016x}-{:016x}.txt", day, hash(text), hash(settings)))
+        }
+    }
+
+    /// Load the recorded answers for the given day, input and settings. If
+    /// none have been recorded, the result is empty.
+    pub fn load(dir: &Path, day: u32, text: &str, settings: &str) -> Result<KnownAnswers, Error> {
+        let path = KnownAnswers::path_for(dir, day, text, settings);
+        let display = path.display().to_string();
+        let answers = match fs::read_to_string(&path) {
+            Ok(data) => parse(&data).map_err(|err| Error::from(err).in_file(&display))?,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => BTreeMap::new(),
+            Err(err) => return Err(Error::Io(Some(display), err)),
+        };
+        Ok(KnownAnswers {
+            path,
+            answers,
+        })
+    }
+
+    pub fn check(&self, part: u32, answer: &Answer) -> Verdict {
+        match self.answers.get(&part) {
+            None => Verdict::Missing,
+            Some(expected) if *expected == answer.value.to_string() => Verdict::Pass,
+            Some(expected) => Verdict::Fail(expected.clone()),
+        }
+    }
+
+    pub fn record(&mut self, part: u32, answer: &Answer) {
+        self.answers.insert(part, answer.value.to_string());
+    }
+
+    /// Write the answers back to their file, creating its directory if
+    /// needed.
+    pub fn save(&self) -> Result<(), Error> {
+        let write = || {
+            if let Some(dir) = self.path.parent() {
+                fs::create_dir_all(dir)?;
+            }
+            let data: String = self.answers.iter().map(|(part, answer)| format!("{}: {}\n", part, answer)).collect();
+            fs::write(&self.path, data)
+        };
+        write().map_err(|err| Error::Write(self.path.display().to_string(), err))
+    }
+}
+
+fn parse(data: &str) -> Result<BTreeMap<u32, String>, ParseError> {
+    let mut answers = BTreeMap::new();
+    for (idx, line) in data.lines().enumerate() {
+        if line.trim().is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let (part, answer) = line.split_once(": ")
+            .ok_or_else(|| ParseError::at_token(idx + 1, line, line, "expected `PART: ANSWER`"))?;
+        let part = crate::error::parse_number(idx + 1, line, part, "a part number")?;
+        answers.insert(part, answer.to_string());
+    }
+    Ok(answers)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hash_is_stable() {
+        assert_eq!(hash(""), 0xcbf29ce484222325);
+        assert_eq!(hash("a"), 0xaf63dc4c8601ec8c);
+    }
+
+    #[test]
+    fn check_answers() {
+        let mut known = KnownAnswers {
+            path: PathBuf::from("day01.txt"),
+            answers: parse("# comment\n1: 7\n").unwrap(),
+        };
+        assert_eq!(known.check(1, &Answer::new("Count", 7)), Verdict::Pass);
+        assert_eq!(known.check(1, &Answer::new("Count", 8)), Verdict::Fail("7".to_string()));
+        assert_eq!(known.check(2, &Answer::new("Count", 5)), Verdict::Missing);
+        known.record(2, &Answer::new("Count", "none"));
+        assert_eq!(known.answers.get(&2).map(|s| s.as_str()), Some("\"none\""));
+    }
+
+    #[test]
+    fn settings_get_their_own_file() {
+        let dir = Path::new("answers");
+        let canonical = KnownAnswers::path_for(dir, 1, "199\n", "");
+        assert_eq!(canonical, dir.join(format!("day01-{:016x}.txt", hash("199\n"))));
+        let windowed = KnownAnswers::path_for(dir, 1, "199\n", "--window 5");
+        assert_ne!(windowed, canonical);
+        assert_ne!(windowed, KnownAnswers::path_for(dir, 1, "199\n", "--window 4"));
+    }
+
+    #[test]
+    fn rejects_bad_line() {
+        assert_eq!(parse("1: 7\ntwo: 5\n").unwrap_err().line, 2);
+    }
+}
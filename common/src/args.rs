@@ -0,0 +1,201 @@
+use std::collections::HashMap;
+use std::env;
+use std::fmt;
+use std::ops::RangeBounds;
+use std::str::FromStr;
+
+/// Command line arguments, split into flags, options that take a value and
+/// positional arguments.
+///
+/// Programs declare which flags and options they accept. An entry may list
+/// aliases separated by `|` (e.g. `"-d|--diagonals"`), and any of the aliases
+/// can be used to query the parsed result. Options take their value from the
+/// following argument or from `--option=value`. -h and --help are always
+/// accepted. A lone `-` is positional, and `--` ends option parsing.
+#[derive(Debug, Default, Clone)]
+pub struct Args {
+    flags: Vec<String>,
+    options: HashMap<String, String>,
+    positional: Vec<String>,
+    help: bool,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum ArgError {
+    UnknownOption(String),
+    MissingValue(String),
+    BadValue(String, String),
+    MissingArgument,
+    UnexpectedArgument(String),
+    /// The option can't be used along with several input files.
+    NotWithBatch(String),
+    /// The option can't be used when reading from standard input.
+    NotWithStdin(String),
+    /// The option can't be used when the input is solved as it's read.
+    NotWithStream(String),
+}
+
+impl fmt::Display for ArgError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ArgError::UnknownOption(opt) => write!(f, "unknown option {}", opt),
+            ArgError::MissingValue(opt) => write!(f, "option {} requires a value", opt),
+            ArgError::BadValue(opt, value) => write!(f, "invalid value {:?} for {}", value, opt),
+            ArgError::MissingArgument => write!(f, "missing argument"),
+            ArgError::UnexpectedArgument(arg) => write!(f, "unexpected argument {:?}", arg),
+            ArgError::NotWithBatch(opt) => write!(f, "{} can't be used with more than one input file", opt),
+            ArgError::NotWithStdin(opt) => write!(f, "{} can't be used when reading standard input", opt),
+            ArgError::NotWithStream(opt) => write!(f, "{} can't be used when streaming the input", opt),
+        }
+    }
+}
+
+impl std::error::Error for ArgError {}
+
+fn find_alias<'a>(specs: &[&'a str], name: &str) -> Option<Vec<&'a str>> {
+    specs.iter()
+        .map(|spec| spec.split('|').collect::<Vec<&str>>())
+        .find(|aliases| aliases.contains(&name))
+}
+
+impl Args {
+    /// Parse arguments, not including the program name.
+    pub fn parse<I>(argv: I, flags: &[&str], options: &[&str]) -> Result<Args, ArgError>
+        where I: IntoIterator<Item = String>
+    {
+        let mut args = Args::default();
+        let mut argv = argv.into_iter();
+        while let Some(arg) = argv.next() {
+            if arg == "--" {
+                args.positional.extend(argv.by_ref());
+                break;
+            }
+            if arg == "-" || !arg.starts_with('-') {
+                args.positional.push(arg);
+                continue;
+            }
+            if arg == "-h" || arg == "--help" {
+                args.help = true;
+                continue;
+            }
+            let (name, inline_value) = match arg.split_once('=') {
+                Some((name, value)) => (name.to_string(), Some(value.to_string())),
+                None => (arg.clone(), None),
+            };
+            if let Some(aliases) = find_alias(flags, &name) {
+                if let Some(value) = inline_value {
+                    return Err(ArgError::BadValue(name, value));
+                }
+                args.flags.extend(aliases.iter().map(|a| a.to_string()));
+            } else if let Some(aliases) = find_alias(options, &name) {
+                let value = match inline_value {
+                    Some(value) => value,
+                    None => argv.next().ok_or_else(|| ArgError::MissingValue(name.clone()))?,
+                };
+                for alias in aliases {
+                    args.options.insert(alias.to_string(), value.clone());
+                }
+            } else {
+                return Err(ArgError::UnknownOption(arg));
+            }
+        }
+        Ok(args)
+    }
+
+    /// Parse the arguments the process was started with.
+    pub fn from_env(flags: &[&str], options: &[&str]) -> Result<Args, ArgError> {
+        Args::parse(env::args().skip(1), flags, options)
+    }
+
+    pub fn wants_help(&self) -> bool {
+        self.help
+    }
+
+    pub fn has_flag(&self, flag: &str) -> bool {
+        self.flags.iter().any(|f| f == flag)
+    }
+
+    pub fn value(&self, option: &str) -> Option<&str> {
+        self.options.get(option).map(|v| v.as_str())
+    }
+
+    /// Get the value of an option converted to `T`, or `None` if the option
+    /// wasn't given.
+    pub fn parsed_value<T: FromStr>(&self, option: &str) -> Result<Option<T>, ArgError> {
+        match self.value(option) {
+            None => Ok(None),
+            Some(value) => value.parse()
+                .map(Some)
+                .map_err(|_| ArgError::BadValue(option.to_string(), value.to_string())),
+        }
+    }
+
+    /// Like `parsed_value`, but values outside of `range` are also rejected.
+    pub fn parsed_value_in<T, R>(&self, option: &str, range: R) -> Result<Option<T>, ArgError>
+        where T: FromStr + PartialOrd, R: RangeBounds<T>
+    {
+        match self.parsed_value(option)? {
+            Some(value) if !range.contains(&value) => {
+                Err(ArgError::BadValue(option.to_string(), self.value(option).unwrap_or_default().to_string()))
+            },
+            value => Ok(value),
+        }
+    }
+
+    /// Give an option a value if it wasn't given one on the command line.
+    pub fn set_default(&mut self, option: &str, value: &str) {
+        self.options.entry(option.to_string()).or_insert_with(|| value.to_string());
+    }
+
+    pub fn positional(&self) -> &[String] {
+        &self.positional
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(argv: &[&str]) -> Result<Args, ArgError> {
+        Args::parse(argv.iter().map(|s| s.to_string()), &["-d|--diagonals"], &["--days"])
+    }
+
+    #[test]
+    fn flags_options_and_positional() {
+        let args = parse(&["--diagonals", "--days", "80", "input.txt"]).unwrap();
+        assert!(args.has_flag("-d"));
+        assert!(args.has_flag("--diagonals"));
+        assert_eq!(args.parsed_value::<u64>("--days"), Ok(Some(80)));
+        assert_eq!(args.positional(), ["input.txt"]);
+    }
+
+    #[test]
+    fn inline_values_and_stdin() {
+        let args = parse(&["--days=256", "-"]).unwrap();
+        assert_eq!(args.value("--days"), Some("256"));
+        assert_eq!(args.positional(), ["-"]);
+    }
+
+    #[test]
+    fn double_dash_ends_options() {
+        let args = parse(&["--", "-d"]).unwrap();
+        assert!(!args.has_flag("-d"));
+        assert_eq!(args.positional(), ["-d"]);
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(parse(&["-x"]).err(), Some(ArgError::UnknownOption("-x".to_string())));
+        assert_eq!(parse(&["--days"]).err(), Some(ArgError::MissingValue("--days".to_string())));
+        let args = parse(&["--days", "many"]).unwrap();
+        assert!(args.parsed_value::<u64>("--days").is_err());
+    }
+
+    #[test]
+    fn value_in_range() {
+        let args = parse(&["--days", "0"]).unwrap();
+        assert_eq!(args.parsed_value_in::<u64, _>("--days", 0..10), Ok(Some(0)));
+        assert_eq!(args.parsed_value_in::<u64, _>("--days", 1..),
+                   Err(ArgError::BadValue("--days".to_string(), "0".to_string())));
+    }
+}
@@ -0,0 +1,225 @@
+use std::fs;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc;
+use std::thread;
+use std::time::Duration;
+
+use crate::bench::{self, Short};
+use crate::solver::{Parts, Puzzle};
+use crate::{error, input, Answer, Args, Error, Format, Json};
+
+/// The outcome of solving one input file.
+#[derive(Debug)]
+pub struct Row {
+    pub file: String,
+    /// Each selected part's number, answer and the time taken to solve it
+    /// (not counting parsing), or what went wrong reading, parsing or solving
+    /// the file.
+    pub result: Result<Vec<(u32, Answer, Duration)>, Error>,
+}
+
+/// Replace each directory among `paths` with the files directly inside it, in
+/// name order.
+pub fn expand(paths: &[String]) -> Result<Vec<String>, Error> {
+    let mut files = Vec::new();
+    for path in paths {
+        if !Path::new(path).is_dir() {
+            files.push(path.clone());
+            continue;
+        }
+        let io_error = |err| Error::Io(Some(path.clone()), err);
+        let mut entries = Vec::new();
+        for entry in fs::read_dir(path).map_err(io_error)? {
+            let entry_path = entry.map_err(io_error)?.path();
+            if entry_path.is_file() {
+                entries.push(entry_path.display().to_string());
+            }
+        }
+        entries.sort();
+        files.extend(entries);
+    }
+    Ok(files)
+}
+
+fn solve<P: Puzzle>(puzzle: &P, file: &str, args: &Args, parts: Parts) -> Row {
+    let solve_all = || {
+        let text = input::read_to_string(file)?;
+        let input = puzzle.parse(&text, args).map_err(|err| err.in_file(input::display_name(file)))?;
+        let mut answers = Vec::new();
+        for part in [1, 2].into_iter().filter(|&part| parts.contains(part)) {
+            let (answer, duration) = bench::time(|| if part == 1 { puzzle.part1(&input) } else { puzzle.part2(&input) });
+            answers.push((part, answer, duration));
+        }
+        Ok(answers)
+    };
+    // a panic is one file's failure, not the whole batch's
+    let result = panic::catch_unwind(AssertUnwindSafe(solve_all))
+        .unwrap_or_else(|payload| Err(Error::Panic(file.to_string(), error::panic_message(&*payload))));
+    Row {
+        file: file.to_string(),
+        result,
+    }
+}
+
+/// Solve every file on a pool of `workers` threads, returning the rows in the
+/// same order as the files. A file whose solution panics gets an error row.
+pub fn solve_all<P: Puzzle>(puzzle: &P, files: &[String], args: &Args, parts: Parts, workers: usize) -> Vec<Row> {
+    // panics end up in their rows, so keep the default hook from printing
+    // them along the way
+    let hook = panic::take_hook();
+    panic::set_hook(Box::new(|_| {}));
+    let next = AtomicUsize::new(0);
+    let (sender, receiver) = mpsc::channel();
+    thread::scope(|scope| {
+        for _ in 0..workers.clamp(1, files.len().max(1)) {
+            let sender = sender.clone();
+            let next = &next;
+            scope.spawn(move || loop {
+                let idx = next.fetch_add(1, Ordering::Relaxed);
+                match files.get(idx) {
+                    Some(file) => sender.send((idx, solve(puzzle, file, args, parts))).unwrap(),
+                    None => break,
+                }
+            });
+        }
+    });
+    panic::set_hook(hook);
+    drop(sender);
+    let mut rows: Vec<(usize, Row)> = receiver.into_iter().collect();
+    rows.sort_by_key(|&(idx, _)| idx);
+    rows.into_iter().map(|(_, row)| row).collect()
+}
+
+/// The number of worker threads to use: one per available CPU.
+pub fn default_workers() -> usize {
+    thread::available_parallelism().map(|n| n.get()).unwrap_or(1)
+}
+
+/// Print the rows as a table of answers and timings, or as JSON with one
+/// object per file and part. Errors are printed to standard error after the
+/// table. Returns the number of files that couldn't be solved.
+pub fn print_rows(format: Format, day: u32, parts: Parts, rows: &[Row]) -> usize {
+    let mut failures = 0;
+    let mut table = vec![vec!["FILE".to_string()]];
+    for part in [1, 2].into_iter().filter(|&part| parts.contains(part)) {
+        table[0].push(format!("PART {}", part));
+        table[0].push("TIME".to_string());
+    }
+    for row in rows {
+        match &row.result {
+            Ok(answers) => {
+                let mut cells = vec![row.file.clone()];
+                for (part, answer, duration) in answers {
+                    cells.push(answer.value.plain());
+                    cells.push(Short(*duration).to_string());
+                    if format == Format::Json {
+                        let mut json = answer.to_json(day, *part);
+                        if let Json::Object(fields) = &mut json {
+                            fields.insert(0, ("file".to_string(), row.file.as_str().into()));
+                            fields.push(("time_ns".to_string(), duration.as_nanos().into()));
+                        }
+                        println!("{}", json);
+                    }
+                }
+                table.push(cells);
+            },
+            Err(err) => {
+                failures += 1;
+                table.push(vec![row.file.clone(), "error".to_string()]);
+                if format == Format::Json {
+                    println!("{}", Json::object(vec![("file", row.file.as_str().into()), ("error", err.to_string().into())]));
+                }
+            },
+        }
+    }
+    if format == Format::Text {
+        print_table(&table);
+    }
+    for row in rows {
+        if let Err(err) = &row.result {
+            eprintln!("{}", err);
+        }
+    }
+    failures
+}
+
+/// Print rows of cells in left-aligned columns.
+fn print_table(table: &[Vec<String>]) {
+    let columns = table.iter().map(|cells| cells.len()).max().unwrap_or(0);
+    let widths: Vec<usize> = (0..columns)
+        .map(|col| table.iter().filter_map(|cells| cells.get(col)).map(|cell| cell.chars().count()).max().unwrap_or(0))
+        .collect();
+    for cells in table {
+        let line = cells.iter()
+            .zip(&widths)
+            .map(|(cell, &width)| format!("{:width$}", cell, width = width))
+            .collect::<Vec<String>>()
+            .join("  ");
+        println!("{}", line.trim_end());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Rng;
+
+    struct Count;
+
+    impl Puzzle for Count {
+        const DAY: u32 = 1;
+        const NAME: &'static str = "Count";
+        const USAGE: &'static str = "";
+        const GEN_USAGE: &'static str = "";
+
+        type Input = usize;
+
+        fn parse(&self, text: &str, _args: &Args) -> Result<usize, Error> {
+            Ok(text.lines().count())
+        }
+
+        fn part1(&self, lines: &usize) -> Answer {
+            Answer::new("Lines", *lines)
+        }
+
+        fn part2(&self, lines: &usize) -> Answer {
+            assert!(*lines != 4, "four lines");
+            Answer::new("Double", *lines * 2)
+        }
+
+        fn generate(&self, _rng: &mut Rng, _args: &Args) -> Result<String, Error> {
+            Ok(String::new())
+        }
+    }
+
+    #[test]
+    fn solves_in_order() {
+        let dir = std::env::temp_dir().join(format!("aoc-batch-test-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        for (name, text) in [("b.txt", "1\n2\n"), ("a.txt", "1\n"), ("c.txt", "1\n2\n3\n"), ("d.txt", "1\n2\n3\n4\n")] {
+            fs::write(dir.join(name), text).unwrap();
+        }
+        let mut files = expand(&[dir.display().to_string()]).unwrap();
+        files.push(dir.join("missing.txt").display().to_string());
+        let rows = solve_all(&Count, &files, &Args::default(), Parts::Both, 3);
+        fs::remove_dir_all(&dir).unwrap();
+
+        let counts: Vec<Option<Vec<String>>> = rows.iter()
+            .map(|row| row.result.as_ref().ok().map(|answers| answers.iter().map(|(_, a, _)| a.value.to_string()).collect()))
+            .collect();
+        assert_eq!(counts, vec![
+            Some(vec!["1".to_string(), "2".to_string()]),
+            Some(vec!["2".to_string(), "4".to_string()]),
+            Some(vec!["3".to_string(), "6".to_string()]),
+            None,
+            None,
+        ]);
+        assert!(rows[0].file.ends_with("a.txt"));
+        match &rows[3].result {
+            Err(Error::Panic(file, message)) => assert!(file.ends_with("d.txt") && message == "four lines"),
+            other => panic!("expected a panic, got {:?}", other),
+        }
+    }
+}
@@ -0,0 +1,162 @@
+use std::fmt;
+use std::time::{Duration, Instant};
+
+use crate::{Format, Json};
+
+/// Run `f` once and return its result along with how long it took.
+pub fn time<T, F: FnOnce() -> T>(f: F) -> (T, Duration) {
+    let start = Instant::now();
+    let result = f();
+    (result, start.elapsed())
+}
+
+/// The durations recorded for one phase of a benchmark (e.g. parsing).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Phase {
+    pub name: String,
+    pub samples: Vec<Duration>,
+}
+
+impl Phase {
+    pub fn min(&self) -> Duration {
+        self.samples.iter().min().copied().unwrap_or_default()
+    }
+
+    pub fn max(&self) -> Duration {
+        self.samples.iter().max().copied().unwrap_or_default()
+    }
+
+    /// The middle sample, or the mean of the middle two if there's an even
+    /// number of them.
+    pub fn median(&self) -> Duration {
+        let mut sorted = self.samples.clone();
+        sorted.sort();
+        match sorted.len() {
+            0 => Duration::default(),
+            len if len % 2 == 1 => sorted[len / 2],
+            len => (sorted[len / 2 - 1] + sorted[len / 2]) / 2,
+        }
+    }
+
+    /// The min, median and max in nanoseconds.
+    pub fn to_json(&self) -> Json {
+        Json::object(vec![
+            ("min_ns", self.min().as_nanos().into()),
+            ("median_ns", self.median().as_nanos().into()),
+            ("max_ns", self.max().as_nanos().into()),
+        ])
+    }
+}
+
+/// Timings for the phases of a puzzle over a number of iterations.
+#[derive(Debug, Clone, Default)]
+pub struct Bench {
+    pub iterations: usize,
+    pub phases: Vec<Phase>,
+}
+
+impl Bench {
+    pub fn new(iterations: usize) -> Bench {
+        Bench {
+            iterations,
+            phases: Vec::new(),
+        }
+    }
+
+    /// Add a sample to the named phase, which is created the first time it
+    /// is seen. Phases are reported in the order they were created.
+    pub fn record(&mut self, name: &str, duration: Duration) {
+        match self.phases.iter_mut().find(|phase| phase.name == name) {
+            Some(phase) => phase.samples.push(duration),
+            None => self.phases.push(Phase {
+                name: name.to_string(),
+                samples: vec![duration],
+            }),
+        }
+    }
+
+    /// The JSON form of the results: `day`, `iterations` and a `phases`
+    /// object keyed by phase name.
+    pub fn to_json(&self, day: u32) -> Json {
+        Json::object(vec![
+            ("day", day.into()),
+            ("iterations", self.iterations.into()),
+            ("phases", Json::Object(self.phases.iter().map(|phase| (phase.name.clone(), phase.to_json())).collect())),
+        ])
+    }
+}
+
+/// A duration rounded to a readable unit, e.g. `1.25ms`.
+pub struct Short(pub Duration);
+
+impl fmt::Display for Short {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let nanos = self.0.as_nanos();
+        let secs = self.0.as_secs_f64();
+        let text = if nanos < 1_000 {
+            format!("{}ns", nanos)
+        } else if nanos < 1_000_000 {
+            format!("{:.2}µs", secs * 1e6)
+        } else if nanos < 1_000_000_000 {
+            format!("{:.2}ms", secs * 1e3)
+        } else {
+            format!("{:.3}s", secs)
+        };
+        // pad so that the durations can be lined up in columns
+        f.pad(&text)
+    }
+}
+
+/// Print benchmark results in the given format.
+pub fn print_bench(format: Format, day: u32, bench: &Bench) {
+    match format {
+        Format::Text => {
+            println!("Day {}, {} iterations:", day, bench.iterations);
+            let width = bench.phases.iter().map(|phase| phase.name.len()).max().unwrap_or(0);
+            for phase in &bench.phases {
+                println!("  {:width$}  min {:>9}  median {:>9}  max {:>9}",
+                         phase.name, Short(phase.min()), Short(phase.median()), Short(phase.max()), width = width);
+            }
+        },
+        Format::Json => println!("{}", bench.to_json(day)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn phase(millis: &[u64]) -> Phase {
+        Phase {
+            name: "parse".to_string(),
+            samples: millis.iter().map(|&ms| Duration::from_millis(ms)).collect(),
+        }
+    }
+
+    #[test]
+    fn min_median_max() {
+        let odd = phase(&[5, 1, 3]);
+        assert_eq!((odd.min(), odd.median(), odd.max()),
+                   (Duration::from_millis(1), Duration::from_millis(3), Duration::from_millis(5)));
+        assert_eq!(phase(&[4, 1, 2, 8]).median(), Duration::from_millis(3));
+    }
+
+    #[test]
+    fn records_in_order() {
+        let mut bench = Bench::new(2);
+        for _ in 0..2 {
+            bench.record("parse", Duration::from_nanos(10));
+            bench.record("part 1", Duration::from_nanos(20));
+        }
+        assert_eq!(bench.to_json(5).to_string(),
+                   r#"{"day":5,"iterations":2,"phases":{"parse":{"min_ns":10,"median_ns":10,"max_ns":10},"part 1":{"min_ns":20,"median_ns":20,"max_ns":20}}}"#);
+    }
+
+    #[test]
+    fn short_units() {
+        assert_eq!(Short(Duration::from_nanos(999)).to_string(), "999ns");
+        assert_eq!(Short(Duration::from_nanos(1_250)).to_string(), "1.25µs");
+        assert_eq!(Short(Duration::from_micros(1_500)).to_string(), "1.50ms");
+        assert_eq!(Short(Duration::from_millis(2_000)).to_string(), "2.000s");
+    }
+}
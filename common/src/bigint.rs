@@ -0,0 +1,496 @@
+use std::cmp::Ordering;
+use std::fmt;
+use std::iter::Sum;
+use std::mem;
+use std::ops::{Add, AddAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+use std::str::FromStr;
+
+use crate::Json;
+
+/// An integer of any size, kept as a sign and a magnitude in 32-bit limbs,
+/// least significant first.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct BigInt {
+    negative: bool,
+    /// Never ends in a zero limb, so zero has no limbs and each value has
+    /// only one representation.
+    limbs: Vec<u32>,
+}
+
+fn compare_magnitudes(a: &[u32], b: &[u32]) -> Ordering {
+    a.len().cmp(&b.len()).then_with(|| a.iter().rev().cmp(b.iter().rev()))
+}
+
+fn add_magnitudes(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let (long, short) = if a.len() >= b.len() { (a, b) } else { (b, a) };
+    let mut sum = Vec::with_capacity(long.len() + 1);
+    let mut carry = 0;
+    for (idx, &limb) in long.iter().enumerate() {
+        let total = u64::from(limb) + u64::from(short.get(idx).copied().unwrap_or(0)) + carry;
+        sum.push(total as u32);
+        carry = total >> 32;
+    }
+    sum.push(carry as u32);
+    sum
+}
+
+/// `a - b`, where `a` is at least as large as `b`.
+fn subtract_magnitudes(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let mut difference = Vec::with_capacity(a.len());
+    let mut borrow = 0;
+    for (idx, &limb) in a.iter().enumerate() {
+        let total = i64::from(limb) - i64::from(b.get(idx).copied().unwrap_or(0)) - borrow;
+        difference.push(total.rem_euclid(1 << 32) as u32);
+        borrow = i64::from(total < 0);
+    }
+    difference
+}
+
+fn multiply_magnitudes(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let mut product = vec![0; a.len() + b.len()];
+    for (i, &x) in a.iter().enumerate() {
+        let mut carry = 0;
+        for (j, &y) in b.iter().enumerate() {
+            let total = u64::from(product[i + j]) + u64::from(x) * u64::from(y) + carry;
+            product[i + j] = total as u32;
+            carry = total >> 32;
+        }
+        product[i + b.len()] = carry as u32;
+    }
+    product
+}
+
+/// Multiply a magnitude by `factor` and add `addend`, in place.
+fn multiply_add_small(limbs: &mut Vec<u32>, factor: u32, addend: u32) {
+    let mut carry = u64::from(addend);
+    for limb in limbs.iter_mut() {
+        let total = u64::from(*limb) * u64::from(factor) + carry;
+        *limb = total as u32;
+        carry = total >> 32;
+    }
+    if carry > 0 {
+        limbs.push(carry as u32);
+    }
+}
+
+/// Divide a magnitude by `divisor` in place, returning the remainder.
+fn divide_small(limbs: &mut Vec<u32>, divisor: u32) -> u32 {
+    let mut remainder = 0;
+    for limb in limbs.iter_mut().rev() {
+        let total = (remainder << 32) | u64::from(*limb);
+        *limb = (total / u64::from(divisor)) as u32;
+        remainder = total % u64::from(divisor);
+    }
+    while limbs.last() == Some(&0) {
+        limbs.pop();
+    }
+    remainder as u32
+}
+
+/// The largest power of ten that fits in a limb, for converting to and from
+/// decimal nine digits at a time.
+const DECIMAL_CHUNK: u32 = 1_000_000_000;
+
+impl BigInt {
+    fn from_parts(negative: bool, mut limbs: Vec<u32>) -> BigInt {
+        while limbs.last() == Some(&0) {
+            limbs.pop();
+        }
+        BigInt {
+            negative: negative && !limbs.is_empty(),
+            limbs,
+        }
+    }
+
+    fn magnitude(&self) -> Option<u128> {
+        if self.limbs.len() > 4 {
+            return None;
+        }
+        Some(self.limbs.iter().rev().fold(0, |acc, &limb| (acc << 32) | u128::from(limb)))
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.limbs.is_empty()
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.negative
+    }
+
+    /// The value as an `i64`, if it fits.
+    pub fn to_i64(&self) -> Option<i64> {
+        let magnitude = self.magnitude()?;
+        if self.negative {
+            if magnitude > u128::from(i64::MIN.unsigned_abs()) {
+                return None;
+            }
+            Some((magnitude as u64).wrapping_neg() as i64)
+        } else {
+            i64::try_from(magnitude).ok()
+        }
+    }
+
+    /// The nearest `f64` to the value, e.g. for scaling a drawing.
+    pub fn to_f64(&self) -> f64 {
+        let magnitude = self.limbs.iter().rev().fold(0.0, |acc, &limb| acc * 4_294_967_296.0 + f64::from(limb));
+        if self.negative { -magnitude } else { magnitude }
+    }
+}
+
+impl From<u128> for BigInt {
+    fn from(n: u128) -> BigInt {
+        BigInt::from_parts(false, (0..4).map(|idx| (n >> (32 * idx)) as u32).collect())
+    }
+}
+
+impl From<i128> for BigInt {
+    fn from(n: i128) -> BigInt {
+        let BigInt { limbs, .. } = BigInt::from(n.unsigned_abs());
+        BigInt::from_parts(n < 0, limbs)
+    }
+}
+
+impl From<i64> for BigInt {
+    fn from(n: i64) -> BigInt {
+        BigInt::from(i128::from(n))
+    }
+}
+
+impl Neg for BigInt {
+    type Output = BigInt;
+
+    fn neg(self) -> BigInt {
+        BigInt::from_parts(!self.negative, self.limbs)
+    }
+}
+
+impl Add for BigInt {
+    type Output = BigInt;
+
+    fn add(self, other: BigInt) -> BigInt {
+        if self.negative == other.negative {
+            return BigInt::from_parts(self.negative, add_magnitudes(&self.limbs, &other.limbs));
+        }
+        match compare_magnitudes(&self.limbs, &other.limbs) {
+            Ordering::Less => BigInt::from_parts(other.negative, subtract_magnitudes(&other.limbs, &self.limbs)),
+            _ => BigInt::from_parts(self.negative, subtract_magnitudes(&self.limbs, &other.limbs)),
+        }
+    }
+}
+
+impl Sub for BigInt {
+    type Output = BigInt;
+
+    fn sub(self, other: BigInt) -> BigInt {
+        self + -other
+    }
+}
+
+impl Mul for BigInt {
+    type Output = BigInt;
+
+    fn mul(self, other: BigInt) -> BigInt {
+        BigInt::from_parts(self.negative != other.negative, multiply_magnitudes(&self.limbs, &other.limbs))
+    }
+}
+
+impl Ord for BigInt {
+    fn cmp(&self, other: &BigInt) -> Ordering {
+        match (self.negative, other.negative) {
+            (false, true) => Ordering::Greater,
+            (true, false) => Ordering::Less,
+            (false, false) => compare_magnitudes(&self.limbs, &other.limbs),
+            (true, true) => compare_magnitudes(&other.limbs, &self.limbs),
+        }
+    }
+}
+
+impl PartialOrd for BigInt {
+    fn partial_cmp(&self, other: &BigInt) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl fmt::Display for BigInt {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut limbs = self.limbs.clone();
+        let mut chunks = Vec::new();
+        while !limbs.is_empty() {
+            chunks.push(divide_small(&mut limbs, DECIMAL_CHUNK));
+        }
+        let mut digits = chunks.pop().unwrap_or(0).to_string();
+        for chunk in chunks.iter().rev() {
+            digits += &format!("{:09}", chunk);
+        }
+        f.pad_integral(!self.negative, "", &digits)
+    }
+}
+
+impl FromStr for BigInt {
+    type Err = ();
+
+    /// Parse a decimal integer with an optional sign.
+    fn from_str(s: &str) -> Result<BigInt, ()> {
+        let (negative, digits) = match s.strip_prefix('-') {
+            Some(digits) => (true, digits),
+            None => (false, s.strip_prefix('+').unwrap_or(s)),
+        };
+        if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(());
+        }
+        let mut limbs = Vec::new();
+        for chunk in digits.as_bytes().chunks(9) {
+            let chunk = std::str::from_utf8(chunk).unwrap();
+            multiply_add_small(&mut limbs, 10u32.pow(chunk.len() as u32), chunk.parse().unwrap());
+        }
+        Ok(BigInt::from_parts(negative, limbs))
+    }
+}
+
+/// An integer that's an `i64` until an operation would overflow it, when it
+/// becomes a `BigInt`. Answers that can grow with the input use this so that
+/// they stay correct however large it is, while the common case stays fast.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Int {
+    Small(i64),
+    /// Only used for values that don't fit in an `i64`, so that each value
+    /// has only one representation.
+    Big(BigInt),
+}
+
+impl Int {
+    pub fn into_big(self) -> BigInt {
+        match self {
+            Int::Small(n) => BigInt::from(n),
+            Int::Big(n) => n,
+        }
+    }
+
+    /// The nearest `f64` to the value, e.g. for scaling a drawing.
+    pub fn to_f64(&self) -> f64 {
+        match self {
+            Int::Small(n) => *n as f64,
+            Int::Big(n) => n.to_f64(),
+        }
+    }
+}
+
+impl Default for Int {
+    fn default() -> Int {
+        Int::Small(0)
+    }
+}
+
+impl From<BigInt> for Int {
+    fn from(n: BigInt) -> Int {
+        match n.to_i64() {
+            Some(small) => Int::Small(small),
+            None => Int::Big(n),
+        }
+    }
+}
+
+macro_rules! int_from_small {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for Int {
+                fn from(n: $t) -> Int {
+                    Int::Small(i64::from(n))
+                }
+            }
+        )*
+    };
+}
+
+int_from_small!(i8, i16, i32, i64, u8, u16, u32);
+
+macro_rules! int_from_wide {
+    ($($t:ty => $wide:ty),*) => {
+        $(
+            impl From<$t> for Int {
+                fn from(n: $t) -> Int {
+                    match i64::try_from(n) {
+                        Ok(small) => Int::Small(small),
+                        Err(_) => Int::Big(BigInt::from(n as $wide)),
+                    }
+                }
+            }
+        )*
+    };
+}
+
+int_from_wide!(u64 => u128, usize => u128, u128 => u128, i128 => i128);
+
+macro_rules! int_op {
+    ($op:ident, $method:ident, $assign:ident, $assign_method:ident, $checked:ident) => {
+        impl<T: Into<Int>> $op<T> for Int {
+            type Output = Int;
+
+            fn $method(self, other: T) -> Int {
+                let other = other.into();
+                if let (Int::Small(a), Int::Small(b)) = (&self, &other) {
+                    if let Some(result) = a.$checked(*b) {
+                        return Int::Small(result);
+                    }
+                }
+                Int::from(self.into_big().$method(other.into_big()))
+            }
+        }
+
+        impl<T: Into<Int>> $assign<T> for Int {
+            fn $assign_method(&mut self, other: T) {
+                *self = mem::take(self).$method(other);
+            }
+        }
+    };
+}
+
+int_op!(Add, add, AddAssign, add_assign, checked_add);
+int_op!(Sub, sub, SubAssign, sub_assign, checked_sub);
+int_op!(Mul, mul, MulAssign, mul_assign, checked_mul);
+
+impl Neg for Int {
+    type Output = Int;
+
+    fn neg(self) -> Int {
+        match self {
+            Int::Small(n) => n.checked_neg().map(Int::Small).unwrap_or_else(|| Int::from(-BigInt::from(n))),
+            Int::Big(n) => Int::from(-n),
+        }
+    }
+}
+
+impl<T: Into<Int>> Sum<T> for Int {
+    fn sum<I: Iterator<Item = T>>(iter: I) -> Int {
+        iter.fold(Int::default(), |total, n| total + n)
+    }
+}
+
+impl Ord for Int {
+    fn cmp(&self, other: &Int) -> Ordering {
+        match (self, other) {
+            (Int::Small(a), Int::Small(b)) => a.cmp(b),
+            _ => self.clone().into_big().cmp(&other.clone().into_big()),
+        }
+    }
+}
+
+impl PartialOrd for Int {
+    fn partial_cmp(&self, other: &Int) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq<i64> for Int {
+    fn eq(&self, other: &i64) -> bool {
+        *self == Int::Small(*other)
+    }
+}
+
+impl PartialOrd<i64> for Int {
+    fn partial_cmp(&self, other: &i64) -> Option<Ordering> {
+        Some(self.cmp(&Int::Small(*other)))
+    }
+}
+
+impl fmt::Display for Int {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Int::Small(n) => fmt::Display::fmt(n, f),
+            Int::Big(n) => fmt::Display::fmt(n, f),
+        }
+    }
+}
+
+impl FromStr for Int {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Int, ()> {
+        s.parse::<BigInt>().map(Int::from)
+    }
+}
+
+impl From<BigInt> for Json {
+    fn from(n: BigInt) -> Json {
+        Json::Number(n.to_string())
+    }
+}
+
+impl From<Int> for Json {
+    fn from(n: Int) -> Json {
+        Json::Number(n.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Rng;
+
+    fn random_i128(rng: &mut Rng) -> i128 {
+        let n = (i128::from(rng.next_u64()) << 64 | i128::from(rng.next_u64())) >> rng.below(127);
+        if rng.chance(0.5) { -n } else { n }
+    }
+
+    #[test]
+    fn matches_i128_arithmetic() {
+        let mut rng = Rng::new(3);
+        for _ in 0..2000 {
+            let (a, b) = (random_i128(&mut rng) >> 1, random_i128(&mut rng) >> 1);
+            let (x, y) = (BigInt::from(a), BigInt::from(b));
+            assert_eq!(x.clone() + y.clone(), BigInt::from(a + b), "{} + {}", a, b);
+            assert_eq!(x.clone() - y.clone(), BigInt::from(a - b), "{} - {}", a, b);
+            assert_eq!(x.cmp(&y), a.cmp(&b), "{} <=> {}", a, b);
+            assert_eq!(x.to_string(), a.to_string());
+            assert_eq!(a.to_string().parse::<BigInt>(), Ok(x.clone()));
+            let (a, b) = (a >> 64, b >> 64);
+            assert_eq!(BigInt::from(a) * BigInt::from(b), BigInt::from(a * b), "{} * {}", a, b);
+        }
+    }
+
+    #[test]
+    fn grows_past_i128() {
+        let two_to_100 = (0..100).fold(BigInt::from(1i64), |n, _| n * BigInt::from(2i64));
+        assert_eq!(two_to_100.to_string(), "1267650600228229401496703205376");
+        let square = two_to_100.clone() * two_to_100.clone();
+        assert_eq!(square.to_string(), "1606938044258990275541962092341162602522202993782792835301376");
+        assert_eq!(square.clone() - square, BigInt::default());
+        assert_eq!(format!("{:>8}", BigInt::from(-42i64)), "     -42");
+        assert!("12a".parse::<BigInt>().is_err());
+        assert!("-".parse::<BigInt>().is_err());
+    }
+
+    #[test]
+    fn promotes_on_overflow() {
+        let max = Int::from(i64::MAX);
+        assert!(matches!(max.clone() + 1, Int::Big(_)));
+        assert_eq!((max.clone() + 1).to_string(), "9223372036854775808");
+        assert_eq!(max.clone() + 1 - 1, max);
+        assert!(matches!(max.clone() + 1 - 1, Int::Small(_)));
+        assert_eq!((max.clone() * max.clone()).to_string(), "85070591730234615847396907784232501249");
+        assert_eq!(-Int::from(i64::MIN), Int::from(i64::MAX) + 1);
+        assert_eq!(Int::from(u64::MAX).to_string(), u64::MAX.to_string());
+        assert!(Int::from(u64::MAX) > i64::MAX);
+        assert!(-Int::from(u64::MAX) < i64::MIN);
+        assert_eq!([5u64, 7, 9].into_iter().sum::<Int>(), 21);
+        assert_eq!(Json::from(Int::from(u128::MAX)).to_string(), u128::MAX.to_string());
+    }
+
+    #[test]
+    fn stays_big_near_u128_limits() {
+        let two_to_127 = 1u128 << 127;
+        for magnitude in [two_to_127 - 1, two_to_127, two_to_127 + 1, u128::MAX] {
+            let n = BigInt::from(magnitude);
+            assert_eq!(n.to_i64(), None);
+            assert_eq!((-n.clone()).to_i64(), None);
+            assert_eq!((Int::from(magnitude) + 0i64).to_string(), magnitude.to_string());
+            assert_eq!((-Int::from(magnitude) - 0i64).to_string(), format!("-{}", magnitude));
+        }
+        let two_to_128 = BigInt::from(u128::MAX) + BigInt::from(1i64);
+        assert_eq!(two_to_128.to_i64(), None);
+        assert_eq!((Int::from(u128::MAX) + 1i64).to_string(), "340282366920938463463374607431768211456");
+        assert_eq!(BigInt::from(i64::MIN).to_i64(), Some(i64::MIN));
+        assert_eq!(BigInt::from(i64::MAX).to_i64(), Some(i64::MAX));
+        assert_eq!((BigInt::from(i64::MIN) - BigInt::from(1i64)).to_i64(), None);
+    }
+}
@@ -0,0 +1,265 @@
+use std::panic::{self, AssertUnwindSafe};
+
+use crate::solver::{self, Puzzle, Solver, COMMON_GEN_OPTIONS};
+use crate::{error, usage, Answer, ArgError, Args, Error, Json, Rng};
+
+pub const USAGE: &str = " [OPTIONS]
+
+Check the day's alternative implementations against its reference parts.
+
+Each case is a random input from the day's generator (see `aoc gen DAY -h` for
+the options it takes). When an implementation's answer differs from the
+reference, or either one panics, the input is shrunk to a small one that still
+shows the difference and printed. Only the answers themselves are compared,
+not their extras.
+
+OPTIONS:
+ --cases N: Number of random inputs to try (default 100)
+ -h: Print this usage message and exit
+
+COMMON OPTIONS:
+ --seed SEED: Seed for the first case, with each case after it using the next
+              seed. Without it, a seed is picked from the clock and printed
+ --size SIZE: How much input to generate for each case (default 20)
+";
+
+/// Default --size for generated cases. Small inputs are quick to solve and
+/// quick to shrink.
+const DEFAULT_SIZE: &str = "20";
+
+/// An alternative implementation of one part of a puzzle, e.g. a faster
+/// algorithm, that should always give the same answer as the reference.
+pub struct Variant<I> {
+    pub name: &'static str,
+    pub part: u32,
+    pub solve: fn(&I) -> Answer,
+}
+
+impl<I> Variant<I> {
+    pub fn new(name: &'static str, part: u32, solve: fn(&I) -> Answer) -> Variant<I> {
+        Variant {
+            name,
+            part,
+            solve,
+        }
+    }
+}
+
+/// The answer from one implementation, or the message it panicked with.
+pub type Outcome = Result<Json, String>;
+
+/// Run `f`, turning a panic into an error holding its message.
+fn catch<F: FnOnce() -> Answer>(f: F) -> Outcome {
+    panic::catch_unwind(AssertUnwindSafe(f))
+        .map(|answer| answer.value)
+        .map_err(|payload| format!("panicked: {}", error::panic_message(&*payload)))
+}
+
+/// Solve `text` with the reference and with `variant`, returning both
+/// outcomes if they differ or the reference panicked, since a panic can't
+/// vouch for the variant even if it panics the same way. Returns `None` if
+/// they agree or the text doesn't parse.
+pub fn disagreement<P: Puzzle>(puzzle: &P, variant: &Variant<P::Input>, text: &str, args: &Args) -> Option<(Outcome, Outcome)> {
+    let input = catch_parse(puzzle, text, args)?;
+    let reference = catch(|| if variant.part == 1 { puzzle.part1(&input) } else { puzzle.part2(&input) });
+    let alternative = catch(|| (variant.solve)(&input));
+    compare(reference, alternative)
+}
+
+fn compare(reference: Outcome, alternative: Outcome) -> Option<(Outcome, Outcome)> {
+    if reference.is_ok() && reference == alternative { None } else { Some((reference, alternative)) }
+}
+
+/// The seeds for `cases` cases starting at `first`, wrapping around after
+/// `u64::MAX`.
+fn seeds(first: u64, cases: u64) -> impl Iterator<Item = u64> {
+    (0..cases).map(move |idx| first.wrapping_add(idx))
+}
+
+fn catch_parse<P: Puzzle>(puzzle: &P, text: &str, args: &Args) -> Option<P::Input> {
+    panic::catch_unwind(AssertUnwindSafe(|| puzzle.parse(text, args).ok())).ok().flatten()
+}
+
+/// Shrink `text` while `fails` still holds for it, by removing lines, or the
+/// comma-separated items of a one-line input, a chunk at a time.
+pub fn minimize<F: Fn(&str) -> bool>(text: &str, fails: F) -> String {
+    let one_line = text.trim_end().lines().count() <= 1;
+    let separator = if one_line { "," } else { "\n" };
+    let join = |items: &[&str]| items.join(separator) + "\n";
+    let mut items: Vec<&str> = if one_line {
+        text.trim_end().split(',').collect()
+    } else {
+        text.lines().collect()
+    };
+    let mut chunk = items.len() / 2;
+    while chunk > 0 {
+        let mut removed = false;
+        let mut start = 0;
+        while start < items.len() {
+            let end = (start + chunk).min(items.len());
+            let candidate: Vec<&str> = items[..start].iter().chain(&items[end..]).copied().collect();
+            if !candidate.is_empty() && fails(&join(&candidate)) {
+                items = candidate;
+                removed = true;
+            } else {
+                start = end;
+            }
+        }
+        if !removed {
+            chunk /= 2;
+        }
+    }
+    join(&items)
+}
+
+fn describe(outcome: &Outcome) -> String {
+    match outcome {
+        Ok(value) => value.plain(),
+        Err(message) => message.clone(),
+    }
+}
+
+/// Run the differential test for a puzzle, printing each implementation's
+/// first disagreement with the reference.
+pub fn run<P: Puzzle>(puzzle: &P, args: &Args) -> Result<(), Error> {
+    let variants = puzzle.variants();
+    if variants.is_empty() {
+        println!("Day {} has no alternative implementations to check", P::DAY);
+        return Ok(());
+    }
+    let cases: u64 = args.parsed_value_in("--cases", 1..)?.unwrap_or(100);
+    let first_seed = match args.parsed_value("--seed")? {
+        Some(seed) => seed,
+        None => {
+            let seed = Rng::time_seed();
+            eprintln!("seed: {}", seed);
+            seed
+        },
+    };
+    let mut args = args.clone();
+    args.set_default("--size", DEFAULT_SIZE);
+
+    // the reference or a variant may panic, which is reported as part of the
+    // disagreement, so keep the default hook from printing it along the way
+    let hook = panic::take_hook();
+    panic::set_hook(Box::new(|_| {}));
+    let mut failed = vec![false; variants.len()];
+    let mut result = Ok(());
+    let mut checked = 0;
+    for seed in seeds(first_seed, cases) {
+        let text = match puzzle.generate(&mut Rng::new(seed), &args) {
+            Ok(text) => text,
+            Err(err) => {
+                result = Err(err);
+                break;
+            },
+        };
+        if catch_parse(puzzle, &text, &args).is_none() {
+            eprintln!("warning: generated input for seed {} doesn't parse; skipping it", seed);
+            continue;
+        }
+        checked += 1;
+        for (variant, failed) in variants.iter().zip(failed.iter_mut()) {
+            if *failed || disagreement(puzzle, variant, &text, &args).is_none() {
+                continue;
+            }
+            *failed = true;
+            let minimized = minimize(&text, |text| disagreement(puzzle, variant, text, &args).is_some());
+            let (reference, alternative) = disagreement(puzzle, variant, &minimized, &args)
+                .expect("the minimized input still disagrees");
+            if reference.is_err() {
+                println!("Part {} reference panics, so implementation {:?} can't be checked (seed {}):", variant.part,
+                         variant.name, seed);
+            } else {
+                println!("Part {} implementation {:?} disagrees with the reference (seed {}):", variant.part,
+                         variant.name, seed);
+            }
+            println!("  reference: {}", describe(&reference));
+            println!("  {}: {}", variant.name, describe(&alternative));
+            println!("Minimized input:");
+            for line in minimized.lines() {
+                println!("  {}", line);
+            }
+        }
+        if failed.iter().all(|&failed| failed) {
+            break;
+        }
+    }
+    panic::set_hook(hook);
+    result?;
+
+    let disagreements = failed.iter().filter(|&&failed| failed).count();
+    if disagreements > 0 {
+        return Err(Error::Disagree(disagreements));
+    }
+    for variant in &variants {
+        println!("Part {} implementation {:?} agrees with the reference on {} cases", variant.part, variant.name,
+                 checked);
+    }
+    Ok(())
+}
+
+/// Run `aoc diff` for a day with the given arguments and return the process
+/// exit code.
+pub fn main<I>(solver: &dyn Solver, argv0: &str, argv: I) -> i32
+    where I: IntoIterator<Item = String>
+{
+    let options = [COMMON_GEN_OPTIONS, &["--cases"], solver.gen_options()].concat();
+    let print = || usage(argv0, USAGE);
+    let args = match Args::parse(argv, &[], &options) {
+        Ok(args) => args,
+        Err(err) => return solver::report(argv0, &err.into(), print),
+    };
+    if args.wants_help() {
+        print();
+        return 0;
+    }
+    if let Some(arg) = args.positional().first() {
+        return solver::report(argv0, &ArgError::UnexpectedArgument(arg.clone()).into(), print);
+    }
+    match solver.diff(&args) {
+        Ok(()) => 0,
+        Err(err) => solver::report(argv0, &err, print),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn minimizes_lines() {
+        let text = "1\n2\n3\n4\n5\n6\n7\n8\n";
+        assert_eq!(minimize(text, |text| text.contains("3\n") && text.contains("6\n")), "3\n6\n");
+    }
+
+    #[test]
+    fn minimizes_comma_separated() {
+        assert_eq!(minimize("1,5,2,7,3\n", |text| text.contains('7')), "7\n");
+    }
+
+    #[test]
+    fn reference_panics_fail() {
+        let panicked = || Err("panicked: oops".to_string());
+        assert_eq!(compare(Ok(3.into()), Ok(3.into())), None);
+        assert!(compare(Ok(3.into()), Ok(4.into())).is_some());
+        assert!(compare(Ok(3.into()), panicked()).is_some());
+        assert!(compare(panicked(), panicked()).is_some());
+    }
+
+    #[test]
+    fn seeds_wrap_around() {
+        assert_eq!(seeds(u64::MAX - 1, 3).collect::<Vec<u64>>(), vec![u64::MAX - 1, u64::MAX, 0]);
+        assert_eq!(seeds(5, 2).collect::<Vec<u64>>(), vec![5, 6]);
+    }
+
+    #[test]
+    fn catches_panics() {
+        let hook = panic::take_hook();
+        panic::set_hook(Box::new(|_| {}));
+        let outcome = catch(|| panic!("oops"));
+        panic::set_hook(hook);
+        assert_eq!(outcome, Err("panicked: oops".to_string()));
+        assert_eq!(catch(|| Answer::new("Count", 3)), Ok(3.into()));
+    }
+}
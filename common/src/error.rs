@@ -0,0 +1,211 @@
+use std::any::Any;
+use std::fmt;
+use std::io;
+use std::str::FromStr;
+
+use crate::ArgError;
+
+/// A problem with the puzzle input, located by line and column.
+///
+/// Lines and columns are 1-based, and columns count characters rather than
+/// bytes. `text` is the offending text, which is empty if something was
+/// missing rather than wrong.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub file: Option<String>,
+    pub line: usize,
+    pub column: usize,
+    pub text: String,
+    pub message: String,
+    pub line_text: String,
+}
+
+impl ParseError {
+    pub fn new(line: usize, column: usize, text: &str, message: &str, line_text: &str) -> ParseError {
+        ParseError {
+            file: None,
+            line,
+            column,
+            text: text.to_string(),
+            message: message.to_string(),
+            line_text: line_text.to_string(),
+        }
+    }
+
+    /// An error about `token`, which must be a slice of `line_text`. The
+    /// column is worked out from where the token sits within the line.
+    pub fn at_token(line: usize, line_text: &str, token: &str, message: &str) -> ParseError {
+        let offset = offset_within(line_text, token).unwrap_or(0);
+        let column = line_text[..offset].chars().count() + 1;
+        ParseError::new(line, column, token, message, line_text)
+    }
+
+    /// An error about something missing at the end of a line.
+    pub fn at_end(line: usize, line_text: &str, message: &str) -> ParseError {
+        ParseError::new(line, line_text.chars().count() + 1, "", message, line_text)
+    }
+
+    /// An error about `token`, which must be a slice of the (possibly
+    /// multi-line) `text`. The line and column are worked out from where the
+    /// token sits within the text.
+    pub fn in_text(text: &str, token: &str, message: &str) -> ParseError {
+        let offset = offset_within(text, token).unwrap_or(0);
+        let line_start = text[..offset].rfind('\n').map(|idx| idx + 1).unwrap_or(0);
+        let line_end = text[offset..].find('\n').map(|idx| offset + idx).unwrap_or(text.len());
+        let line = text[..offset].matches('\n').count() + 1;
+        let line_text = text[line_start..line_end].trim_end_matches('\r');
+        ParseError::at_token(line, line_text, token, message)
+    }
+
+    /// Set the line number, for errors from parsers that only see one line.
+    pub fn on_line(mut self, line: usize) -> ParseError {
+        self.line = line;
+        self
+    }
+
+    pub fn in_file(mut self, file: &str) -> ParseError {
+        if self.file.is_none() {
+            self.file = Some(file.to_string());
+        }
+        self
+    }
+}
+
+fn offset_within(outer: &str, inner: &str) -> Option<usize> {
+    let offset = (inner.as_ptr() as usize).checked_sub(outer.as_ptr() as usize)?;
+    if offset + inner.len() <= outer.len() {
+        Some(offset)
+    } else {
+        None
+    }
+}
+
+/// Parse `token`, a slice of `line_text`, as a number, or explain what was
+/// expected.
+pub fn parse_number<T: FromStr>(line: usize, line_text: &str, token: &str, expected: &str) -> Result<T, ParseError> {
+    token.parse().map_err(|_| {
+        ParseError::at_token(line, line_text, token, &format!("expected {}, found {:?}", expected, token))
+    })
+}
+
+impl fmt::Display for ParseError {
+    /// Formats the error the way rustc does, pointing at the offending text.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let line_no = self.line.to_string();
+        let gutter = " ".repeat(line_no.len());
+        writeln!(f, "error: {}", self.message)?;
+        writeln!(f, "{}--> {}:{}:{}", gutter, self.file.as_deref().unwrap_or("<input>"), self.line, self.column)?;
+        writeln!(f, "{} |", gutter)?;
+        writeln!(f, "{} | {}", line_no, self.line_text)?;
+        write!(f, "{} | {}{}", gutter, " ".repeat(self.column - 1), "^".repeat(self.text.chars().count().max(1)))
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Anything that can go wrong while running a solution.
+#[derive(Debug)]
+pub enum Error {
+    Args(ArgError),
+    Io(Option<String>, io::Error),
+    Write(String, io::Error),
+    /// An input couldn't be downloaded.
+    Fetch(String),
+    Parse(ParseError),
+    /// Some number of parts didn't match their recorded answers.
+    Verify(usize),
+    /// Some number of files in a batch couldn't be solved. Their errors have
+    /// already been printed.
+    Batch(usize),
+    /// Some number of alternative implementations disagreed with the
+    /// reference. The disagreements have already been printed.
+    Disagree(usize),
+    /// Solving a file panicked, with the panic's message.
+    Panic(String, String),
+    /// A picture couldn't be drawn for --render.
+    Render(String),
+}
+
+impl Error {
+    /// Attach a file name to an error that doesn't have one yet.
+    pub fn in_file(self, file: &str) -> Error {
+        match self {
+            Error::Io(None, err) => Error::Io(Some(file.to_string()), err),
+            Error::Parse(err) => Error::Parse(err.in_file(file)),
+            other => other,
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Args(err) => write!(f, "{}", err),
+            Error::Io(file, err) => write!(f, "error: couldn't read {}: {}", file.as_deref().unwrap_or("input"), err),
+            Error::Write(file, err) => write!(f, "error: couldn't write {}: {}", file, err),
+            Error::Fetch(message) => write!(f, "error: {}", message),
+            Error::Parse(err) => write!(f, "{}", err),
+            Error::Verify(1) => write!(f, "error: 1 part failed verification"),
+            Error::Verify(count) => write!(f, "error: {} parts failed verification", count),
+            Error::Batch(1) => write!(f, "error: 1 input couldn't be solved"),
+            Error::Batch(count) => write!(f, "error: {} inputs couldn't be solved", count),
+            Error::Disagree(1) => write!(f, "error: 1 implementation disagreed with the reference"),
+            Error::Disagree(count) => write!(f, "error: {} implementations disagreed with the reference", count),
+            Error::Panic(file, message) => write!(f, "error: solving {} panicked: {}", file, message),
+            Error::Render(message) => write!(f, "error: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// The message a panic was raised with, from the payload that
+/// `std::panic::catch_unwind` returns.
+pub fn panic_message(payload: &(dyn Any + Send)) -> String {
+    payload.downcast_ref::<&str>().map(|s| s.to_string())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_default()
+}
+
+impl From<ArgError> for Error {
+    fn from(err: ArgError) -> Error {
+        Error::Args(err)
+    }
+}
+
+impl From<ParseError> for Error {
+    fn from(err: ParseError) -> Error {
+        Error::Parse(err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn token_column() {
+        let line = "8,0 -> 0,x8";
+        let err = ParseError::at_token(2, line, &line[9..], "bad");
+        assert_eq!((err.line, err.column), (2, 10));
+    }
+
+    #[test]
+    fn position_in_text() {
+        let text = "1,2\n3,x,4\n";
+        let err = ParseError::in_text(text, &text[6..7], "bad");
+        assert_eq!((err.line, err.column), (2, 3));
+        assert_eq!(err.line_text, "3,x,4");
+    }
+
+    #[test]
+    fn diagnostic() {
+        let err = ParseError::at_token(3, "sideways 5", &"sideways 5"[..8], "unknown direction").in_file("input.txt");
+        assert_eq!(err.to_string(), "\
+error: unknown direction
+ --> input.txt:3:1
+  |
+3 | sideways 5
+  | ^^^^^^^^");
+    }
+}
@@ -0,0 +1,280 @@
+use std::env;
+use std::fs;
+use std::io::{self, prelude::*};
+use std::net::TcpStream;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use crate::solver::{self, Solver};
+use crate::{usage, ArgError, Args, Error};
+
+/// Where inputs are downloaded from unless --base-url or `AOC_BASE_URL` says
+/// otherwise.
+pub const DEFAULT_BASE_URL: &str = "https://adventofcode.com/2021";
+
+const USER_AGENT: &str = "aoc2021-runner (std-only fetcher)";
+
+pub const USAGE: &str = " [OPTIONS]
+
+Download the day's puzzle input into the cache, so that the solution can be
+run without a FILENAME. A day that's already cached is never downloaded again.
+
+The input is fetched from BASE_URL/day/DAY/input using the session token from
+the AOC_SESSION environment variable, or else from the file `session` in the
+cache directory. The cache directory is $AOC_CACHE_DIR if set, and otherwise
+$XDG_CACHE_HOME/aoc or ~/.cache/aoc.
+
+http:// URLs are fetched directly. https:// URLs are fetched with curl, which
+must be installed.
+
+OPTIONS:
+ --base-url URL: Where to download from (default: $AOC_BASE_URL, or
+                 https://adventofcode.com/2021)
+ -h: Print this usage message and exit
+";
+
+/// The per-user directory holding fetched inputs, if one can be found.
+pub fn cache_dir() -> Option<PathBuf> {
+    let var = |name| env::var_os(name).filter(|value| !value.is_empty()).map(PathBuf::from);
+    var("AOC_CACHE_DIR")
+        .or_else(|| var("XDG_CACHE_HOME").map(|dir| dir.join("aoc")))
+        .or_else(|| var("HOME").map(|dir| dir.join(".cache").join("aoc")))
+}
+
+/// The path a day's input is cached at within `dir`.
+pub fn input_path(dir: &Path, day: u32) -> PathBuf {
+    dir.join(format!("day{:02}.txt", day))
+}
+
+/// The cached input for a day, if it's been fetched.
+pub fn cached_input(day: u32) -> Option<PathBuf> {
+    cache_dir().map(|dir| input_path(&dir, day)).filter(|path| path.is_file())
+}
+
+/// Everything needed to fetch inputs.
+#[derive(Debug, Clone)]
+pub struct Config {
+    pub base_url: String,
+    pub session: Option<String>,
+    pub cache_dir: PathBuf,
+}
+
+impl Config {
+    /// Settings from the environment, with the base URL overridden by
+    /// --base-url if it was given.
+    pub fn from_env(args: &Args) -> Result<Config, Error> {
+        let cache_dir = cache_dir().ok_or_else(|| Error::Fetch("couldn't find a cache directory; set AOC_CACHE_DIR".to_string()))?;
+        let session = env::var("AOC_SESSION").ok()
+            .or_else(|| fs::read_to_string(cache_dir.join("session")).ok())
+            .map(|session| session.trim().to_string())
+            .filter(|session| !session.is_empty());
+        let base_url = args.value("--base-url").map(|url| url.to_string())
+            .or_else(|| env::var("AOC_BASE_URL").ok())
+            .unwrap_or_else(|| DEFAULT_BASE_URL.to_string());
+        Ok(Config {
+            base_url,
+            session,
+            cache_dir,
+        })
+    }
+}
+
+/// Make sure a day's input is in the cache, downloading it if it isn't yet.
+/// Returns the cached file and whether it had to be downloaded.
+pub fn fetch(day: u32, config: &Config) -> Result<(PathBuf, bool), Error> {
+    let path = input_path(&config.cache_dir, day);
+    if path.is_file() {
+        return Ok((path, false));
+    }
+    let session = config.session.as_deref()
+        .ok_or_else(|| Error::Fetch("no session token; set AOC_SESSION or write it to the cache's `session` file".to_string()))?;
+    let url = format!("{}/day/{}/input", config.base_url.trim_end_matches('/'), day);
+    let body = get(&url, session)?;
+    let write = || {
+        fs::create_dir_all(&config.cache_dir)?;
+        // write to a temporary file first so that a failed write can't leave
+        // a partial input that would never be refetched
+        let partial = path.with_extension("part");
+        fs::write(&partial, &body)?;
+        fs::rename(&partial, &path)
+    };
+    write().map_err(|err| Error::Write(path.display().to_string(), err))?;
+    Ok((path, true))
+}
+
+/// Download `url` with the given session cookie and return the body.
+pub fn get(url: &str, session: &str) -> Result<String, Error> {
+    if let Some(rest) = url.strip_prefix("http://") {
+        http_get(url, rest, session)
+    } else if url.starts_with("https://") {
+        curl_get(url, session)
+    } else {
+        Err(Error::Fetch(format!("unsupported URL {:?}; expected http:// or https://", url)))
+    }
+}
+
+/// A plain HTTP/1.1 GET. `rest` is the URL without its scheme.
+fn http_get(url: &str, rest: &str, session: &str) -> Result<String, Error> {
+    let (host, path) = match rest.find('/') {
+        Some(idx) => (&rest[..idx], &rest[idx..]),
+        None => (rest, "/"),
+    };
+    let address = if host.contains(':') { host.to_string() } else { format!("{}:80", host) };
+    let io_error = |err| Error::Io(Some(url.to_string()), err);
+    let mut stream = TcpStream::connect(&address).map_err(io_error)?;
+    write!(stream, "GET {} HTTP/1.1\r\nHost: {}\r\nUser-Agent: {}\r\nCookie: session={}\r\nConnection: close\r\n\r\n",
+           path, host, USER_AGENT, session).map_err(io_error)?;
+    let mut response = Vec::new();
+    stream.read_to_end(&mut response).map_err(io_error)?;
+    let response = String::from_utf8_lossy(&response);
+    let (head, body) = response.split_once("\r\n\r\n")
+        .ok_or_else(|| Error::Fetch(format!("malformed response from {}", url)))?;
+    let status = head.split_whitespace().nth(1).unwrap_or_default();
+    if status != "200" {
+        let reason = head.lines().next().unwrap_or_default();
+        return Err(Error::Fetch(format!("couldn't fetch {}: {}", url, reason)));
+    }
+    let chunked = head.lines().any(|line| {
+        let line = line.to_ascii_lowercase();
+        line.starts_with("transfer-encoding:") && line.contains("chunked")
+    });
+    if chunked {
+        dechunk(body).ok_or_else(|| Error::Fetch(format!("malformed chunked response from {}", url)))
+    } else {
+        Ok(body.to_string())
+    }
+}
+
+/// Decode a body sent with chunked transfer encoding.
+fn dechunk(mut body: &str) -> Option<String> {
+    let mut text = String::new();
+    loop {
+        let (size, rest) = body.split_once("\r\n")?;
+        let size = usize::from_str_radix(size.split(';').next()?.trim(), 16).ok()?;
+        if size == 0 {
+            return Some(text);
+        }
+        text += rest.get(..size)?;
+        body = rest.get(size..)?.strip_prefix("\r\n")?;
+    }
+}
+
+/// An HTTPS GET using curl, since std has no TLS. The cookie is passed on
+/// standard input so that it doesn't show up in the process list.
+fn curl_get(url: &str, session: &str) -> Result<String, Error> {
+    let io_error = |err| Error::Io(Some(url.to_string()), err);
+    let mut child = Command::new("curl")
+        .args(["--silent", "--show-error", "--fail", "--user-agent", USER_AGENT, "--header", "@-", url])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|err| match err.kind() {
+            io::ErrorKind::NotFound => Error::Fetch("curl is needed to fetch https:// URLs".to_string()),
+            _ => io_error(err),
+        })?;
+    if let Some(mut stdin) = child.stdin.take() {
+        writeln!(stdin, "Cookie: session={}", session).map_err(io_error)?;
+    }
+    let output = child.wait_with_output().map_err(io_error)?;
+    if !output.status.success() {
+        let message = String::from_utf8_lossy(&output.stderr).trim().to_string();
+        return Err(Error::Fetch(format!("couldn't fetch {}: {}", url, message)));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Print the fetch command's usage message.
+pub fn print_usage(argv0: &str) {
+    usage(argv0, USAGE);
+}
+
+/// Run `aoc fetch` for a day with the given arguments and return the process
+/// exit code.
+pub fn main<I>(solver: &dyn Solver, argv0: &str, argv: I) -> i32
+    where I: IntoIterator<Item = String>
+{
+    let print = || print_usage(argv0);
+    let args = match Args::parse(argv, &[], &["--base-url"]) {
+        Ok(args) => args,
+        Err(err) => return solver::report(argv0, &err.into(), print),
+    };
+    if args.wants_help() {
+        print();
+        return 0;
+    }
+    if let Some(arg) = args.positional().first() {
+        return solver::report(argv0, &ArgError::UnexpectedArgument(arg.clone()).into(), print);
+    }
+    match Config::from_env(&args).and_then(|config| fetch(solver.day(), &config)) {
+        Ok((path, true)) => {
+            eprintln!("Fetched day {} into {}", solver.day(), path.display());
+            0
+        },
+        Ok((path, false)) => {
+            eprintln!("Day {} is already cached in {}", solver.day(), path.display());
+            0
+        },
+        Err(err) => solver::report(argv0, &err, print),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::TcpListener;
+    use std::thread;
+
+    /// Serve one canned response on a local port, and return the base URL
+    /// along with a handle that yields the request that was received.
+    fn stand_in_server(response: &'static str) -> (String, thread::JoinHandle<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let base_url = format!("http://{}/2021", listener.local_addr().unwrap());
+        let handle = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = Vec::new();
+            let mut buf = [0; 1024];
+            while !request.ends_with(b"\r\n\r\n") {
+                let len = stream.read(&mut buf).unwrap();
+                request.extend_from_slice(&buf[..len]);
+            }
+            stream.write_all(response.as_bytes()).unwrap();
+            String::from_utf8(request).unwrap()
+        });
+        (base_url, handle)
+    }
+
+    #[test]
+    fn fetches_once_into_cache() {
+        let (base_url, server) = stand_in_server("HTTP/1.1 200 OK\r\nContent-Length: 6\r\n\r\n1\n2\n3\n");
+        let cache_dir = env::temp_dir().join(format!("aoc-fetch-test-{}", std::process::id()));
+        let config = Config {
+            base_url,
+            session: Some("abc123".to_string()),
+            cache_dir: cache_dir.clone(),
+        };
+        let (path, fetched) = fetch(1, &config).unwrap();
+        let request = server.join().unwrap();
+        assert!(request.starts_with("GET /2021/day/1/input HTTP/1.1\r\n"));
+        assert!(request.contains("\r\nCookie: session=abc123\r\n"));
+        assert!(fetched);
+        assert_eq!(fs::read_to_string(&path).unwrap(), "1\n2\n3\n");
+        // the server is gone, so this only works because the input is cached
+        assert_eq!(fetch(1, &config).unwrap(), (path, false));
+        fs::remove_dir_all(cache_dir).unwrap();
+    }
+
+    #[test]
+    fn reports_http_errors() {
+        let (base_url, server) = stand_in_server("HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\n\r\n");
+        let err = get(&format!("{}/day/26/input", base_url), "abc123").unwrap_err();
+        server.join().unwrap();
+        assert!(err.to_string().ends_with("HTTP/1.1 404 Not Found"), "{}", err);
+    }
+
+    #[test]
+    fn decodes_chunks() {
+        assert_eq!(dechunk("3\r\n1\n2\r\n2;x=y\r\n\n3\r\n0\r\n\r\n").as_deref(), Some("1\n2\n3"));
+        assert_eq!(dechunk("5\r\n1\n"), None);
+    }
+}
@@ -0,0 +1,246 @@
+use std::ops::{Index, IndexMut};
+use std::str::FromStr;
+
+use crate::error::{self, ParseError};
+
+/// A rectangular grid of cells, stored row by row. Positions are `(x, y)`,
+/// with `(0, 0)` at the top left.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+/// Offsets to the neighbors above, left, right and below a cell.
+const ORTHOGONAL: [(isize, isize); 4] = [(0, -1), (-1, 0), (1, 0), (0, 1)];
+
+/// Offsets to all eight neighbors of a cell, row by row.
+const ALL_AROUND: [(isize, isize); 8] = [(-1, -1), (0, -1), (1, -1), (-1, 0), (1, 0), (-1, 1), (0, 1), (1, 1)];
+
+impl<T> Grid<T> {
+    /// A grid with every cell set to `fill`.
+    pub fn new(width: usize, height: usize, fill: T) -> Grid<T>
+        where T: Clone
+    {
+        Grid {
+            width,
+            height,
+            cells: vec![fill; width * height],
+        }
+    }
+
+    /// A grid from its rows, or `None` if they aren't all the same length.
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Option<Grid<T>> {
+        let width = rows.first().map(|row| row.len()).unwrap_or(0);
+        if rows.iter().any(|row| row.len() != width) {
+            return None;
+        }
+        Some(Grid {
+            width,
+            height: rows.len(),
+            cells: rows.into_iter().flatten().collect(),
+        })
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn contains(&self, x: usize, y: usize) -> bool {
+        x < self.width && y < self.height
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> Option<&T> {
+        if self.contains(x, y) { self.cells.get(y * self.width + x) } else { None }
+    }
+
+    pub fn get_mut(&mut self, x: usize, y: usize) -> Option<&mut T> {
+        if self.contains(x, y) { self.cells.get_mut(y * self.width + x) } else { None }
+    }
+
+    /// Every position in the grid, row by row.
+    pub fn positions(&self) -> impl Iterator<Item = (usize, usize)> {
+        let width = self.width;
+        (0..self.height).flat_map(move |y| (0..width).map(move |x| (x, y)))
+    }
+
+    /// Every cell in the grid, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.cells.iter()
+    }
+
+    /// Every cell along with its position, row by row.
+    pub fn enumerate(&self) -> impl Iterator<Item = ((usize, usize), &T)> {
+        self.positions().zip(self.cells.iter())
+    }
+
+    /// The positions reached from `(x, y)` by each of `offsets` that stay
+    /// within the grid.
+    fn offset_positions(&self, x: usize, y: usize, offsets: &'static [(isize, isize)])
+        -> impl Iterator<Item = (usize, usize)> + '_
+    {
+        offsets.iter()
+            .filter_map(move |&(dx, dy)| Some((x.checked_add_signed(dx)?, y.checked_add_signed(dy)?)))
+            .filter(|&(x, y)| self.contains(x, y))
+    }
+
+    /// The positions above, left, right and below `(x, y)` that are within
+    /// the grid.
+    pub fn neighbors4(&self, x: usize, y: usize) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.offset_positions(x, y, &ORTHOGONAL)
+    }
+
+    /// The positions of all eight cells around `(x, y)` that are within the
+    /// grid, including diagonal ones.
+    pub fn neighbors8(&self, x: usize, y: usize) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.offset_positions(x, y, &ALL_AROUND)
+    }
+
+    /// The cells in row `y`, left to right. Empty if there's no such row.
+    pub fn row(&self, y: usize) -> impl Iterator<Item = &T> {
+        let start = if y < self.height { y * self.width } else { 0 };
+        let len = if y < self.height { self.width } else { 0 };
+        self.cells[start..start + len].iter()
+    }
+
+    /// The cells in column `x`, top to bottom. Empty if there's no such
+    /// column.
+    pub fn column(&self, x: usize) -> impl Iterator<Item = &T> {
+        let height = if x < self.width { self.height } else { 0 };
+        (0..height).map(move |y| &self.cells[y * self.width + x])
+    }
+
+    /// The cells on the diagonal running down and to the right from
+    /// `(x, y)`, starting with that cell.
+    pub fn diagonal(&self, x: usize, y: usize) -> impl Iterator<Item = &T> {
+        (0..).map_while(move |step| self.get(x + step, y + step))
+    }
+
+    /// The cells on the diagonal running down and to the left from `(x, y)`,
+    /// starting with that cell.
+    pub fn anti_diagonal(&self, x: usize, y: usize) -> impl Iterator<Item = &T> {
+        (0..=x).map_while(move |step| self.get(x - step, y + step))
+    }
+
+    /// A grid of the same size with `f` applied to every cell.
+    pub fn map<U, F: FnMut(&T) -> U>(&self, f: F) -> Grid<U> {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self.cells.iter().map(f).collect(),
+        }
+    }
+}
+
+impl Grid<u8> {
+    /// Parse a grid with one digit per cell, e.g. a heightmap.
+    pub fn parse_digits(text: &str) -> Result<Grid<u8>, ParseError> {
+        parse_rows(text, |line_no, line| {
+            line.char_indices()
+                .map(|(col, c)| {
+                    c.to_digit(10).map(|digit| digit as u8).ok_or_else(|| {
+                        let message = format!("expected a digit from 0 to 9, found {:?}", c);
+                        ParseError::at_token(line_no, line, &line[col..col + c.len_utf8()], &message)
+                    })
+                })
+                .collect()
+        })
+    }
+}
+
+impl<T: FromStr> Grid<T> {
+    /// Parse a grid of whitespace-separated values, e.g. a bingo board.
+    /// `expected` describes a value for error messages, e.g. "a number".
+    pub fn parse_whitespace(text: &str, expected: &str) -> Result<Grid<T>, ParseError> {
+        parse_rows(text, |line_no, line| {
+            line.split_whitespace()
+                .map(|token| error::parse_number(line_no, line, token, expected))
+                .collect()
+        })
+    }
+}
+
+/// Parse each line of `text` as a row with `parse_row`, which is given the
+/// line number and the line, and check that the rows are all the same width.
+fn parse_rows<T, F>(text: &str, parse_row: F) -> Result<Grid<T>, ParseError>
+    where F: Fn(usize, &str) -> Result<Vec<T>, ParseError>
+{
+    let mut rows: Vec<Vec<T>> = Vec::new();
+    for (idx, line) in text.lines().enumerate() {
+        let line_no = idx + 1;
+        let row = parse_row(line_no, line)?;
+        if let Some(first) = rows.first() {
+            if row.len() != first.len() {
+                let message = format!("expected {} cells like the first row, found {}", first.len(), row.len());
+                return Err(ParseError::at_token(line_no, line, line, &message));
+            }
+        }
+        rows.push(row);
+    }
+    Ok(Grid::from_rows(rows).unwrap())
+}
+
+impl<T> Index<(usize, usize)> for Grid<T> {
+    type Output = T;
+
+    /// The cell at `(x, y)`. Panics if it's outside the grid.
+    fn index(&self, (x, y): (usize, usize)) -> &T {
+        assert!(self.contains(x, y), "({}, {}) is outside of a {}x{} grid", x, y, self.width, self.height);
+        &self.cells[y * self.width + x]
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for Grid<T> {
+    fn index_mut(&mut self, (x, y): (usize, usize)) -> &mut T {
+        assert!(self.contains(x, y), "({}, {}) is outside of a {}x{} grid", x, y, self.width, self.height);
+        &mut self.cells[y * self.width + x]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn neighbors_stay_inside() {
+        let grid = Grid::new(3, 2, 0);
+        assert_eq!(grid.neighbors4(0, 0).collect::<Vec<_>>(), vec![(1, 0), (0, 1)]);
+        assert_eq!(grid.neighbors4(1, 1).collect::<Vec<_>>(), vec![(1, 0), (0, 1), (2, 1)]);
+        assert_eq!(grid.neighbors8(2, 1).collect::<Vec<_>>(), vec![(1, 0), (2, 0), (1, 1)]);
+        assert_eq!(grid.neighbors8(1, 0).count(), 5);
+        assert_eq!(grid.get(3, 0), None);
+    }
+
+    #[test]
+    fn lines_through_the_grid() {
+        let grid = Grid::parse_digits("123\n456\n789\n").unwrap();
+        assert_eq!((grid.width(), grid.height()), (3, 3));
+        assert_eq!(grid.row(1).copied().collect::<Vec<u8>>(), vec![4, 5, 6]);
+        assert_eq!(grid.column(2).copied().collect::<Vec<u8>>(), vec![3, 6, 9]);
+        assert_eq!(grid.diagonal(0, 0).copied().collect::<Vec<u8>>(), vec![1, 5, 9]);
+        assert_eq!(grid.anti_diagonal(2, 0).copied().collect::<Vec<u8>>(), vec![3, 5, 7]);
+        assert_eq!(grid.diagonal(1, 0).copied().collect::<Vec<u8>>(), vec![2, 6]);
+        assert_eq!(grid.row(3).count(), 0);
+        assert_eq!(grid[(0, 2)], 7);
+    }
+
+    #[test]
+    fn parses_whitespace_separated() {
+        let grid: Grid<u32> = Grid::parse_whitespace("22 13  1\n 8  2 23\n", "a number").unwrap();
+        assert_eq!(grid.column(0).copied().collect::<Vec<u32>>(), vec![22, 8]);
+        let err = Grid::<u32>::parse_whitespace("1 2\n3 x\n", "a number").unwrap_err();
+        assert_eq!((err.line, err.column), (2, 3));
+    }
+
+    #[test]
+    fn rejects_ragged_rows() {
+        let err = Grid::parse_digits("123\n12\n").unwrap_err();
+        assert_eq!(err.line, 2);
+        assert_eq!(Grid::from_rows(vec![vec![1], vec![]]), None);
+    }
+}
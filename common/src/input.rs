@@ -0,0 +1,47 @@
+use std::fs::File;
+use std::io::{self, prelude::*, BufReader};
+
+use crate::Error;
+
+/// The file name that means "read from standard input".
+pub const STDIN: &str = "-";
+
+/// The name to use for an input file in messages.
+pub fn display_name(filename: &str) -> &str {
+    if filename == STDIN { "<stdin>" } else { filename }
+}
+
+/// Open a puzzle input file, or standard input if `filename` is `-`, for
+/// buffered reading.
+pub fn open(filename: &str) -> Result<Box<dyn BufRead>, Error> {
+    if filename == STDIN {
+        return Ok(Box::new(BufReader::new(io::stdin())));
+    }
+    let file = File::open(filename).map_err(|err| Error::Io(Some(filename.to_string()), err))?;
+    Ok(Box::new(BufReader::new(file)))
+}
+
+/// Read a whole puzzle input file, or standard input if `filename` is `-`,
+/// into a string.
+pub fn read_to_string(filename: &str) -> Result<String, Error> {
+    let mut text = String::new();
+    open(filename)?
+        .read_to_string(&mut text)
+        .map_err(|err| Error::Io(Some(display_name(filename).to_string()), err))?;
+    Ok(text)
+}
+
+/// Read everything from a reader into a string.
+pub fn read_all<R: Read>(mut reader: R) -> Result<String, Error> {
+    let mut text = String::new();
+    reader.read_to_string(&mut text).map_err(|err| Error::Io(None, err))?;
+    Ok(text)
+}
+
+/// Read all of the lines from a reader. Pass `text.as_bytes()` to read from a
+/// string.
+pub fn lines<R: BufRead>(reader: R) -> Result<Vec<String>, Error> {
+    reader.lines()
+        .collect::<Result<Vec<String>, _>>()
+        .map_err(|err| Error::Io(None, err))
+}
@@ -0,0 +1,161 @@
+use std::fmt;
+
+/// A JSON value, with a serializer that needs nothing outside of std.
+///
+/// Numbers are kept as their decimal text so that integers of any size
+/// survive intact.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Json {
+    Null,
+    Bool(bool),
+    Number(String),
+    String(String),
+    Array(Vec<Json>),
+    Object(Vec<(String, Json)>),
+}
+
+impl Json {
+    /// Build an object from key/value pairs, keeping their order.
+    pub fn object(fields: Vec<(&str, Json)>) -> Json {
+        Json::Object(fields.into_iter().map(|(key, value)| (key.to_string(), value)).collect())
+    }
+
+    /// Format the value for people rather than programs: strings aren't
+    /// quoted and arrays are written as comma-separated lists.
+    pub fn plain(&self) -> String {
+        match self {
+            Json::String(s) => s.clone(),
+            Json::Array(items) => items.iter().map(|item| item.plain()).collect::<Vec<String>>().join(", "),
+            other => other.to_string(),
+        }
+    }
+}
+
+fn write_string(f: &mut fmt::Formatter, s: &str) -> fmt::Result {
+    write!(f, "\"")?;
+    for c in s.chars() {
+        match c {
+            '"' => write!(f, "\\\"")?,
+            '\\' => write!(f, "\\\\")?,
+            '\n' => write!(f, "\\n")?,
+            '\r' => write!(f, "\\r")?,
+            '\t' => write!(f, "\\t")?,
+            c if (c as u32) < 0x20 => write!(f, "\\u{:04x}", c as u32)?,
+            c => write!(f, "{}", c)?,
+        }
+    }
+    write!(f, "\"")
+}
+
+impl fmt::Display for Json {
+    /// Serializes the value as compact JSON.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Json::Null => write!(f, "null"),
+            Json::Bool(b) => write!(f, "{}", b),
+            Json::Number(n) => write!(f, "{}", n),
+            Json::String(s) => write_string(f, s),
+            Json::Array(items) => {
+                write!(f, "[")?;
+                for (idx, item) in items.iter().enumerate() {
+                    if idx > 0 {
+                        write!(f, ",")?;
+                    }
+                    write!(f, "{}", item)?;
+                }
+                write!(f, "]")
+            },
+            Json::Object(fields) => {
+                write!(f, "{{")?;
+                for (idx, (key, value)) in fields.iter().enumerate() {
+                    if idx > 0 {
+                        write!(f, ",")?;
+                    }
+                    write_string(f, key)?;
+                    write!(f, ":{}", value)?;
+                }
+                write!(f, "}}")
+            },
+        }
+    }
+}
+
+macro_rules! json_number {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for Json {
+                fn from(n: $t) -> Json {
+                    Json::Number(n.to_string())
+                }
+            }
+        )*
+    };
+}
+
+json_number!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+
+impl From<f64> for Json {
+    /// Infinities and NaN have no JSON form, so they become `null`.
+    fn from(n: f64) -> Json {
+        if n.is_finite() { Json::Number(n.to_string()) } else { Json::Null }
+    }
+}
+
+impl From<bool> for Json {
+    fn from(b: bool) -> Json {
+        Json::Bool(b)
+    }
+}
+
+impl From<&str> for Json {
+    fn from(s: &str) -> Json {
+        Json::String(s.to_string())
+    }
+}
+
+impl From<String> for Json {
+    fn from(s: String) -> Json {
+        Json::String(s)
+    }
+}
+
+impl<T: Into<Json>> From<Vec<T>> for Json {
+    fn from(items: Vec<T>) -> Json {
+        Json::Array(items.into_iter().map(|item| item.into()).collect())
+    }
+}
+
+impl<T: Into<Json>> From<Option<T>> for Json {
+    fn from(value: Option<T>) -> Json {
+        value.map(|value| value.into()).unwrap_or(Json::Null)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serializes_compactly() {
+        let value = Json::object(vec![
+            ("day", 9.into()),
+            ("sizes", vec![14, 9, 9].into()),
+            ("note", Json::Null),
+            ("ok", true.into()),
+        ]);
+        assert_eq!(value.to_string(), r#"{"day":9,"sizes":[14,9,9],"note":null,"ok":true}"#);
+    }
+
+    #[test]
+    fn escapes_strings() {
+        assert_eq!(Json::from("a \"b\"\\\n\u{1}").to_string(), r#""a \"b\"\\\n\u0001""#);
+    }
+
+    #[test]
+    fn plain() {
+        assert_eq!(Json::from("none").plain(), "none");
+        assert_eq!(Json::from(vec![1, 2]).plain(), "1, 2");
+        assert_eq!(Json::from(227.6).plain(), "227.6");
+        assert_eq!(Json::from(f64::NAN), Json::Null);
+    }
+}
@@ -0,0 +1,37 @@
+//! Helpers shared by all of the daily solutions: argument parsing, input
+//! loading, logging, grids, big integers, answer printing, rendering,
+//! benchmarking, random input generation, differential testing and watching
+//! inputs for changes.
+
+pub mod answers;
+pub mod args;
+pub mod batch;
+pub mod bench;
+pub mod bigint;
+pub mod diff;
+pub mod error;
+pub mod fetch;
+pub mod grid;
+pub mod input;
+pub mod json;
+pub mod log;
+pub mod output;
+pub mod render;
+pub mod rng;
+pub mod solver;
+pub mod watch;
+
+pub use args::{ArgError, Args};
+pub use bigint::{BigInt, Int};
+pub use error::{Error, ParseError};
+pub use grid::Grid;
+pub use json::Json;
+pub use output::{Answer, Format};
+pub use rng::Rng;
+pub use solver::{Puzzle, Solver};
+
+/// Print a program's usage message. `usage` is the text that follows the
+/// program name, starting with the synopsis.
+pub fn usage(argv0: &str, usage: &str) {
+    print!("{}{}", argv0, usage);
+}
@@ -0,0 +1,111 @@
+use std::fmt;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU8, Ordering};
+
+use crate::{Args, Error};
+
+/// How much detail a message goes into. Each level includes the ones before
+/// it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Level {
+    /// Something looks wrong but the solver carries on.
+    Warn = 1,
+    /// A summary of what the solver found, e.g. how many values are left.
+    Info,
+    /// Each decision the solver makes, e.g. each bingo number called.
+    Debug,
+    /// Everything, e.g. each cell looked at.
+    Trace,
+}
+
+impl fmt::Display for Level {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.pad(match self {
+            Level::Warn => "warning",
+            Level::Info => "info",
+            Level::Debug => "debug",
+            Level::Trace => "trace",
+        })
+    }
+}
+
+impl FromStr for Level {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Level, ()> {
+        match s {
+            "warn" | "warning" => Ok(Level::Warn),
+            "info" => Ok(Level::Info),
+            "debug" => Ok(Level::Debug),
+            "trace" => Ok(Level::Trace),
+            _ => Err(()),
+        }
+    }
+}
+
+static MAX_LEVEL: AtomicU8 = AtomicU8::new(Level::Warn as u8);
+
+/// Only show messages at `level` or below from now on.
+pub fn set_max_level(level: Level) {
+    MAX_LEVEL.store(level as u8, Ordering::Relaxed);
+}
+
+/// Whether messages at `level` are shown. Check this before doing any work
+/// that's only needed for a message.
+pub fn enabled(level: Level) -> bool {
+    level as u8 <= MAX_LEVEL.load(Ordering::Relaxed)
+}
+
+/// The level asked for with --trace or --log, defaulting to warnings only.
+pub fn selected_level(args: &Args) -> Result<Level, Error> {
+    if args.has_flag("--trace") {
+        return Ok(Level::Trace);
+    }
+    Ok(args.parsed_value("--log")?.unwrap_or(Level::Warn))
+}
+
+/// Print a message to standard error if its level is enabled. Use the
+/// `warn!`, `info!`, `debug!` and `trace!` macros rather than calling this.
+pub fn log(level: Level, args: fmt::Arguments) {
+    if enabled(level) {
+        eprintln!("{}: {}", level, args);
+    }
+}
+
+#[macro_export]
+macro_rules! warn {
+    ($($arg:tt)*) => { $crate::log::log($crate::log::Level::Warn, format_args!($($arg)*)) };
+}
+
+#[macro_export]
+macro_rules! info {
+    ($($arg:tt)*) => { $crate::log::log($crate::log::Level::Info, format_args!($($arg)*)) };
+}
+
+#[macro_export]
+macro_rules! debug {
+    ($($arg:tt)*) => { $crate::log::log($crate::log::Level::Debug, format_args!($($arg)*)) };
+}
+
+#[macro_export]
+macro_rules! trace {
+    ($($arg:tt)*) => { $crate::log::log($crate::log::Level::Trace, format_args!($($arg)*)) };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(argv: &[&str]) -> Args {
+        Args::parse(argv.iter().map(|s| s.to_string()), &["--trace"], &["--log"]).unwrap()
+    }
+
+    #[test]
+    fn picks_level() {
+        assert_eq!(selected_level(&args(&[])).unwrap(), Level::Warn);
+        assert_eq!(selected_level(&args(&["--log", "debug"])).unwrap(), Level::Debug);
+        assert_eq!(selected_level(&args(&["--log", "info", "--trace"])).unwrap(), Level::Trace);
+        assert!(selected_level(&args(&["--log", "loud"])).is_err());
+        assert!(Level::Info < Level::Debug);
+    }
+}
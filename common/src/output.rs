@@ -0,0 +1,105 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::Json;
+
+/// The answer to one part of a puzzle, along with a label describing it and
+/// any extra values worth reporting (e.g. the position that produced it).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Answer {
+    pub label: String,
+    pub value: Json,
+    pub extras: Vec<(String, Json)>,
+}
+
+impl Answer {
+    pub fn new<T: Into<Json>>(label: &str, value: T) -> Answer {
+        Answer {
+            label: label.to_string(),
+            value: value.into(),
+            extras: Vec::new(),
+        }
+    }
+
+    /// Attach an extra named value to the answer.
+    pub fn with<T: Into<Json>>(mut self, name: &str, value: T) -> Answer {
+        self.extras.push((name.to_string(), value.into()));
+        self
+    }
+
+    /// The stable JSON form of the answer: `day`, `part`, `label`, `answer`
+    /// and an `extras` object.
+    pub fn to_json(&self, day: u32, part: u32) -> Json {
+        self.json(day, part.into())
+    }
+
+    /// The JSON form of a fact from a report about the input (see
+    /// `Puzzle::report`), which has the same keys as an answer's but with a
+    /// null `part`, since it doesn't belong to either part.
+    pub fn to_report_json(&self, day: u32) -> Json {
+        self.json(day, Json::Null)
+    }
+
+    fn json(&self, day: u32, part: Json) -> Json {
+        Json::object(vec![
+            ("day", day.into()),
+            ("part", part),
+            ("label", self.label.as_str().into()),
+            ("answer", self.value.clone()),
+            ("extras", Json::Object(self.extras.clone())),
+        ])
+    }
+}
+
+impl fmt::Display for Answer {
+    /// Formats as e.g. `Sum: 61229`, followed by any extras in parentheses.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}: {}", self.label, self.value.plain())?;
+        if !self.extras.is_empty() {
+            let extras = self.extras.iter()
+                .map(|(name, value)| format!("{}: {}", name, value.plain()))
+                .collect::<Vec<String>>();
+            write!(f, " ({})", extras.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+/// How answers are printed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Format {
+    /// One human-readable line per part.
+    #[default]
+    Text,
+    /// One JSON object per part, one per line.
+    Json,
+}
+
+impl FromStr for Format {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Format, ()> {
+        match s {
+            "text" => Ok(Format::Text),
+            "json" => Ok(Format::Json),
+            _ => Err(()),
+        }
+    }
+}
+
+/// Print one fact from a report about the input (see `Puzzle::report`) in
+/// the given format.
+pub fn print_report(format: Format, day: u32, fact: &Answer) {
+    match format {
+        Format::Text => println!("{}", fact),
+        Format::Json => println!("{}", fact.to_report_json(day)),
+    }
+}
+
+/// Print one part's answer in the given format.
+pub fn print_answer(format: Format, day: u32, part: u32, answer: &Answer) {
+    match format {
+        Format::Text => println!("{}", answer),
+        Format::Json => println!("{}", answer.to_json(day, part)),
+    }
+}
@@ -0,0 +1,310 @@
+use std::fmt::Write as _;
+use std::fs;
+use std::io::{self, IsTerminal, Write};
+use std::path::Path;
+
+use crate::input::STDIN;
+use crate::{Error, Grid};
+
+pub const USAGE: &str = "
+RENDERING:
+ --render PATH draws a picture of the puzzle after printing the answers. The
+ kind of picture depends on PATH's extension: .ppm for a PPM image, .svg for
+ an SVG image, .ans for text with ANSI colors, and anything else for plain
+ text. Use - as PATH to draw on the terminal, in color if it's a terminal.
+";
+
+/// About how many pixels across the longer side of an image should be.
+/// Small pictures get several pixels per cell to make up for it, up to
+/// `MAX_SCALE`.
+const IMAGE_SIZE: usize = 1000;
+const MAX_SCALE: usize = 8;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Color(pub u8, pub u8, pub u8);
+
+impl Color {
+    pub const BLACK: Color = Color(0, 0, 0);
+    pub const WHITE: Color = Color(255, 255, 255);
+    pub const GREY: Color = Color(110, 110, 110);
+    pub const RED: Color = Color(220, 50, 47);
+    pub const ORANGE: Color = Color(240, 140, 30);
+    pub const YELLOW: Color = Color(235, 200, 40);
+    pub const GREEN: Color = Color(90, 190, 60);
+    pub const CYAN: Color = Color(40, 180, 190);
+    pub const BLUE: Color = Color(60, 110, 220);
+    pub const MAGENTA: Color = Color(200, 70, 170);
+
+    /// This color with its brightness scaled by `percent`.
+    pub fn dimmed(self, percent: u32) -> Color {
+        let scale = |c: u8| (u32::from(c) * percent.min(100) / 100) as u8;
+        Color(scale(self.0), scale(self.1), scale(self.2))
+    }
+}
+
+/// One character cell of a picture. Cells without a color are background.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cell {
+    pub glyph: char,
+    pub color: Option<Color>,
+}
+
+impl Cell {
+    pub const BLANK: Cell = Cell {
+        glyph: ' ',
+        color: None,
+    };
+}
+
+/// A picture made of character cells, each with its own color, that can be
+/// drawn as text or as an image.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Picture {
+    pub cells: Grid<Cell>,
+    pub background: Color,
+}
+
+impl Picture {
+    /// A blank picture.
+    pub fn new(width: usize, height: usize) -> Picture {
+        Picture {
+            cells: Grid::new(width, height, Cell::BLANK),
+            background: Color::BLACK,
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.cells.width()
+    }
+
+    pub fn height(&self) -> usize {
+        self.cells.height()
+    }
+
+    /// Set the cell at `(x, y)`. Cells outside the picture are ignored.
+    pub fn set(&mut self, x: usize, y: usize, glyph: char, color: Color) {
+        if let Some(cell) = self.cells.get_mut(x, y) {
+            *cell = Cell {
+                glyph,
+                color: Some(color),
+            };
+        }
+    }
+
+    /// Write `text` left to right starting at `(x, y)`. Spaces are left as
+    /// background.
+    pub fn text(&mut self, x: usize, y: usize, text: &str, color: Color) {
+        for (idx, glyph) in text.chars().enumerate() {
+            if glyph != ' ' {
+                self.set(x + idx, y, glyph, color);
+            }
+        }
+    }
+
+    /// Draw a straight line of `glyph` from one cell to another, including
+    /// both ends. Parts of the line outside the picture are left out.
+    pub fn line(&mut self, from: (i64, i64), to: (i64, i64), glyph: char, color: Color) {
+        let (dx, dy) = ((to.0 - from.0).abs(), -(to.1 - from.1).abs());
+        let (step_x, step_y) = ((to.0 - from.0).signum(), (to.1 - from.1).signum());
+        let (mut x, mut y) = from;
+        let mut err = dx + dy;
+        loop {
+            if let (Ok(cx), Ok(cy)) = (usize::try_from(x), usize::try_from(y)) {
+                self.set(cx, cy, glyph, color);
+            }
+            if (x, y) == to {
+                break;
+            }
+            let e2 = 2 * err;
+            if e2 >= dy {
+                err += dy;
+                x += step_x;
+            }
+            if e2 <= dx {
+                err += dx;
+                y += step_y;
+            }
+        }
+    }
+
+    /// The picture as plain text, without trailing spaces.
+    pub fn to_ascii(&self) -> String {
+        let mut text = String::new();
+        for y in 0..self.height() {
+            let line: String = self.cells.row(y).map(|cell| cell.glyph).collect();
+            text += line.trim_end();
+            text.push('\n');
+        }
+        text
+    }
+
+    /// The picture as text, with each cell colored using 24-bit ANSI escape
+    /// codes, and without trailing spaces.
+    pub fn to_ansi(&self) -> String {
+        let mut text = String::new();
+        for y in 0..self.height() {
+            let cells: Vec<&Cell> = self.cells.row(y).collect();
+            let len = cells.iter().rposition(|cell| cell.glyph != ' ').map(|idx| idx + 1).unwrap_or(0);
+            let mut current = None;
+            for cell in &cells[..len] {
+                if cell.color != current && cell.glyph != ' ' {
+                    match cell.color {
+                        Some(Color(r, g, b)) => write!(text, "\x1b[38;2;{};{};{}m", r, g, b).unwrap(),
+                        None => text += "\x1b[0m",
+                    }
+                    current = cell.color;
+                }
+                text.push(cell.glyph);
+            }
+            if current.is_some() {
+                text += "\x1b[0m";
+            }
+            text.push('\n');
+        }
+        text
+    }
+
+    /// The number of pixels along each side of a cell in an image.
+    pub fn scale(&self) -> usize {
+        (IMAGE_SIZE / self.width().max(self.height()).max(1)).clamp(1, MAX_SCALE)
+    }
+
+    /// The picture as a binary PPM image, with each cell as a square of
+    /// pixels filled with its color.
+    pub fn to_ppm(&self) -> Vec<u8> {
+        let scale = self.scale();
+        let mut image = format!("P6\n{} {}\n255\n", self.width() * scale, self.height() * scale).into_bytes();
+        for y in 0..self.height() {
+            let mut row = Vec::with_capacity(self.width() * scale * 3);
+            for cell in self.cells.row(y) {
+                let Color(r, g, b) = cell.color.unwrap_or(self.background);
+                for _ in 0..scale {
+                    row.extend_from_slice(&[r, g, b]);
+                }
+            }
+            for _ in 0..scale {
+                image.extend_from_slice(&row);
+            }
+        }
+        image
+    }
+
+    /// The picture as an SVG image, with each run of same-colored cells in a
+    /// row drawn as one rectangle on top of the background.
+    pub fn to_svg(&self) -> String {
+        let (width, height) = (self.width(), self.height());
+        let mut svg = String::new();
+        writeln!(svg, r#"<svg xmlns="http://www.w3.org/2000/svg" width="{}" height="{}" viewBox="0 0 {} {}" shape-rendering="crispEdges">"#,
+                 width * self.scale(), height * self.scale(), width, height).unwrap();
+        let Color(r, g, b) = self.background;
+        writeln!(svg, r#"<rect width="{}" height="{}" fill="rgb({},{},{})"/>"#, width, height, r, g, b).unwrap();
+        for y in 0..height {
+            let row: Vec<Option<Color>> = self.cells.row(y)
+                .map(|cell| cell.color.filter(|&color| color != self.background))
+                .collect();
+            let mut x = 0;
+            while x < width {
+                let run = row[x..].iter().take_while(|&&color| color == row[x]).count();
+                if let Some(Color(r, g, b)) = row[x] {
+                    writeln!(svg, r#"<rect x="{}" y="{}" width="{}" height="1" fill="rgb({},{},{})"/>"#, x, y, run, r, g, b).unwrap();
+                }
+                x += run;
+            }
+        }
+        svg += "</svg>\n";
+        svg
+    }
+}
+
+/// How a picture gets drawn.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Backend {
+    Ascii,
+    Ansi,
+    Ppm,
+    Svg,
+}
+
+impl Backend {
+    /// The backend for writing to `path`, going by its extension. Standard
+    /// output gets color only if it's a terminal.
+    pub fn for_path(path: &str) -> Backend {
+        if path == STDIN {
+            return if io::stdout().is_terminal() { Backend::Ansi } else { Backend::Ascii };
+        }
+        match Path::new(path).extension().and_then(|ext| ext.to_str()) {
+            Some("ppm") => Backend::Ppm,
+            Some("svg") => Backend::Svg,
+            Some("ans") => Backend::Ansi,
+            _ => Backend::Ascii,
+        }
+    }
+
+    pub fn draw(&self, picture: &Picture) -> Vec<u8> {
+        match self {
+            Backend::Ascii => picture.to_ascii().into_bytes(),
+            Backend::Ansi => picture.to_ansi().into_bytes(),
+            Backend::Ppm => picture.to_ppm(),
+            Backend::Svg => picture.to_svg().into_bytes(),
+        }
+    }
+}
+
+/// Draw a picture to `path`, or to standard output if it's `-`.
+pub fn write(picture: &Picture, path: &str) -> Result<(), Error> {
+    let drawing = Backend::for_path(path).draw(picture);
+    if path == STDIN {
+        io::stdout().write_all(&drawing).map_err(|err| Error::Write("standard output".to_string(), err))
+    } else {
+        fs::write(path, drawing).map_err(|err| Error::Write(path.to_string(), err))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> Picture {
+        let mut picture = Picture::new(4, 2);
+        picture.text(0, 0, "ab", Color::RED);
+        picture.set(3, 1, '#', Color::BLUE);
+        picture
+    }
+
+    #[test]
+    fn draws_text() {
+        assert_eq!(sample().to_ascii(), "ab\n   #\n");
+        assert_eq!(sample().to_ansi(), "\x1b[38;2;220;50;47mab\x1b[0m\n   \x1b[38;2;60;110;220m#\x1b[0m\n");
+    }
+
+    #[test]
+    fn draws_images() {
+        let ppm = sample().to_ppm();
+        let header = format!("P6\n{} {}\n255\n", 4 * MAX_SCALE, 2 * MAX_SCALE);
+        assert!(ppm.starts_with(header.as_bytes()));
+        assert_eq!(ppm.len(), header.len() + 4 * 2 * MAX_SCALE * MAX_SCALE * 3);
+        assert_eq!(&ppm[header.len()..header.len() + 3], &[220, 50, 47]);
+
+        let svg = sample().to_svg();
+        assert!(svg.contains(r#"<rect x="0" y="0" width="2" height="1" fill="rgb(220,50,47)"/>"#));
+        assert!(svg.contains(r#"<rect x="3" y="1" width="1" height="1" fill="rgb(60,110,220)"/>"#));
+        assert!(svg.ends_with("</svg>\n"));
+        assert_eq!(Picture::new(2000, 10).scale(), 1);
+    }
+
+    #[test]
+    fn draws_lines() {
+        let mut picture = Picture::new(4, 3);
+        picture.line((0, 0), (3, 2), '*', Color::WHITE);
+        picture.line((3, 0), (3, -5), '|', Color::WHITE);
+        assert_eq!(picture.to_ascii(), "*  |\n **\n   *\n");
+    }
+
+    #[test]
+    fn picks_backend_by_extension() {
+        assert_eq!(Backend::for_path("vents.ppm"), Backend::Ppm);
+        assert_eq!(Backend::for_path("out/basins.svg"), Backend::Svg);
+        assert_eq!(Backend::for_path("boards.ans"), Backend::Ansi);
+        assert_eq!(Backend::for_path("boards.txt"), Backend::Ascii);
+    }
+}
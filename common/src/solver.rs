@@ -0,0 +1,505 @@
+use std::env;
+use std::io::BufRead;
+use std::str::FromStr;
+
+use std::hint::black_box;
+use std::path::Path;
+
+use crate::answers::{self, KnownAnswers};
+use crate::batch;
+use crate::bench::{self, Bench};
+use crate::diff::{self, Variant};
+use crate::output::{self, Answer, Format};
+use crate::render::{self, Picture};
+use crate::{fetch, input, log, usage, watch, ArgError, Args, Error, Rng};
+
+/// A single day's solution, as registered with the `aoc` runner.
+pub trait Solver {
+    /// The day of the puzzle, from 1 through 25.
+    fn day(&self) -> u32;
+
+    /// The title of the puzzle.
+    fn name(&self) -> &'static str;
+
+    /// The text that follows the program name in the usage message, starting
+    /// with the synopsis.
+    fn usage(&self) -> &'static str;
+
+    /// Flags accepted by the solution, in the format used by `Args::parse`.
+    fn flags(&self) -> &'static [&'static str];
+
+    /// Options that take a value, in the format used by `Args::parse`.
+    fn options(&self) -> &'static [&'static str];
+
+    /// Run the solution and print its answers.
+    fn run(&self, args: &Args) -> Result<(), Error>;
+
+    /// The text that follows the program name in the input generator's usage
+    /// message, starting with the synopsis.
+    fn gen_usage(&self) -> &'static str;
+
+    /// Options accepted by the input generator, in the format used by
+    /// `Args::parse`.
+    fn gen_options(&self) -> &'static [&'static str];
+
+    /// Generate a random, valid puzzle input.
+    fn generate(&self, rng: &mut Rng, args: &Args) -> Result<String, Error>;
+
+    /// Check the alternative implementations of the parts against the
+    /// reference on generated inputs, printing any disagreement.
+    fn diff(&self, args: &Args) -> Result<(), Error>;
+}
+
+/// A day's puzzle, split into parsing and the two parts so that its answers
+/// are available as values. Every `Puzzle` is also a `Solver`. Puzzles are
+/// shared between threads in batch mode, hence `Sync`.
+pub trait Puzzle: Sync {
+    const DAY: u32;
+    const NAME: &'static str;
+    const USAGE: &'static str;
+    const FLAGS: &'static [&'static str] = &[];
+    const OPTIONS: &'static [&'static str] = &[];
+    const GEN_USAGE: &'static str;
+    const GEN_OPTIONS: &'static [&'static str] = &[];
+    /// The FLAGS and OPTIONS that change the answers. --record and --verify
+    /// keep separate answers for each combination of them that's given.
+    const ANSWER_OPTIONS: &'static [&'static str] = &[];
+
+    /// The parsed puzzle input, along with any settings taken from the
+    /// command line.
+    type Input;
+
+    fn parse(&self, text: &str, args: &Args) -> Result<Self::Input, Error>;
+    fn part1(&self, input: &Self::Input) -> Answer;
+    fn part2(&self, input: &Self::Input) -> Answer;
+
+    /// Which parts to run. By default this is whatever --part asked for.
+    fn parts(&self, args: &Args) -> Result<Parts, Error> {
+        selected_parts(args)
+    }
+
+    /// Generate a random input that `parse` accepts. `args` holds the
+    /// generator's options, including --size.
+    fn generate(&self, rng: &mut Rng, args: &Args) -> Result<String, Error>;
+
+    /// Other implementations of the parts, e.g. faster algorithms, for
+    /// `aoc diff` to check against `part1` and `part2`. There are none by
+    /// default.
+    fn variants(&self) -> Vec<Variant<Self::Input>> {
+        Vec::new()
+    }
+
+    /// A picture of the puzzle for --render to draw. Days that can draw one
+    /// list --render among their OPTIONS.
+    fn render(&self, _input: &Self::Input, _args: &Args) -> Result<Option<Picture>, Error> {
+        Ok(None)
+    }
+
+    /// Facts about the input beyond the answers, printed after them, when
+    /// `args` asks for them (e.g. with --stats). There are none by default.
+    fn report(&self, _input: &Self::Input, _args: &Args) -> Vec<Answer> {
+        Vec::new()
+    }
+
+    /// Whether to solve with `solve_stream`, reading the input a line at a
+    /// time, rather than with `parse`. Days that can do without holding the
+    /// whole input say so when `args` asks for it.
+    fn streams(&self, _args: &Args) -> bool {
+        false
+    }
+
+    /// Solve the selected parts while reading the input, returning each
+    /// part's number and answer. Only called when `streams` is true.
+    fn solve_stream(&self, _reader: &mut dyn BufRead, _args: &Args, _parts: Parts)
+        -> Result<Vec<(u32, Answer)>, Error>
+    {
+        unreachable!("day {} can't solve a stream", Self::DAY)
+    }
+}
+
+/// Which parts of a puzzle to run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Parts {
+    One,
+    Two,
+    #[default]
+    Both,
+}
+
+impl Parts {
+    pub fn contains(&self, part: u32) -> bool {
+        match self {
+            Parts::One => part == 1,
+            Parts::Two => part == 2,
+            Parts::Both => part == 1 || part == 2,
+        }
+    }
+}
+
+impl FromStr for Parts {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Parts, ()> {
+        match s {
+            "1" => Ok(Parts::One),
+            "2" => Ok(Parts::Two),
+            "both" => Ok(Parts::Both),
+            _ => Err(()),
+        }
+    }
+}
+
+/// The `options` given in `args`, with their values, as one string such as
+/// `--window 5 --raw`, or an empty string if none of them were given.
+pub fn answer_settings(args: &Args, options: &[&str]) -> String {
+    let given: Vec<String> = options.iter()
+        .filter_map(|&option| match args.value(option) {
+            Some(value) => Some(format!("{} {}", option, value)),
+            None => args.has_flag(option).then(|| option.to_string()),
+        })
+        .collect();
+    given.join(" ")
+}
+
+/// The parts asked for with --part, defaulting to both.
+pub fn selected_parts(args: &Args) -> Result<Parts, Error> {
+    Ok(args.parsed_value("--part")?.unwrap_or_default())
+}
+
+impl<P: Puzzle> Solver for P {
+    fn day(&self) -> u32 {
+        P::DAY
+    }
+
+    fn name(&self) -> &'static str {
+        P::NAME
+    }
+
+    fn usage(&self) -> &'static str {
+        P::USAGE
+    }
+
+    fn flags(&self) -> &'static [&'static str] {
+        P::FLAGS
+    }
+
+    fn options(&self) -> &'static [&'static str] {
+        P::OPTIONS
+    }
+
+    fn run(&self, args: &Args) -> Result<(), Error> {
+        log::set_max_level(log::selected_level(args)?);
+        let is_batch = args.positional().len() > 1
+            || args.positional().first().map(|path| Path::new(path).is_dir()).unwrap_or(false);
+        if is_batch {
+            return run_batch(self, args);
+        }
+        let filename = match args.positional().first() {
+            Some(filename) => filename.clone(),
+            None => fetch::cached_input(P::DAY).ok_or(ArgError::MissingArgument)?.display().to_string(),
+        };
+        if self.streams(args) {
+            return run_stream(self, &filename, args);
+        }
+        let text = input::read_to_string(&filename)?;
+        let input = self.parse(&text, args).map_err(|err| err.in_file(input::display_name(&filename)))?;
+        let format: Format = args.parsed_value("--format")?.unwrap_or_default();
+        let parts = self.parts(args)?;
+        if let Some(iterations) = args.parsed_value_in("--bench", 1..)? {
+            bench::print_bench(format, P::DAY, &benchmark(self, &text, args, parts, iterations)?);
+            return Ok(());
+        }
+        let verify = args.has_flag("--verify");
+        let record = args.has_flag("--record");
+        let mut known = if verify || record {
+            let dir = args.value("--answers").unwrap_or(answers::DEFAULT_DIR);
+            Some(KnownAnswers::load(Path::new(dir), P::DAY, &text, &answer_settings(args, P::ANSWER_OPTIONS))?)
+        } else {
+            None
+        };
+        let mut failures = 0;
+        for part in [1, 2].into_iter().filter(|&part| parts.contains(part)) {
+            crate::info!("solving part {}", part);
+            let answer = if part == 1 { self.part1(&input) } else { self.part2(&input) };
+            match known.as_mut() {
+                Some(known) if verify => {
+                    let verdict = known.check(part, &answer);
+                    if !verdict.passed() {
+                        failures += 1;
+                    }
+                    answers::print_verdict(format, P::DAY, part, &answer, &verdict);
+                },
+                Some(known) => {
+                    known.record(part, &answer);
+                    output::print_answer(format, P::DAY, part, &answer);
+                },
+                None => output::print_answer(format, P::DAY, part, &answer),
+            }
+        }
+        if let Some(known) = known.filter(|_| record && !verify) {
+            known.save()?;
+            eprintln!("Recorded answers in {}", known.path.display());
+        }
+        for fact in self.report(&input, args) {
+            output::print_report(format, P::DAY, &fact);
+        }
+        if let Some(path) = args.value("--render") {
+            if let Some(picture) = self.render(&input, args)? {
+                render::write(&picture, path)?;
+            }
+        }
+        if failures > 0 {
+            return Err(Error::Verify(failures));
+        }
+        Ok(())
+    }
+
+    fn gen_usage(&self) -> &'static str {
+        P::GEN_USAGE
+    }
+
+    fn gen_options(&self) -> &'static [&'static str] {
+        P::GEN_OPTIONS
+    }
+
+    fn generate(&self, rng: &mut Rng, args: &Args) -> Result<String, Error> {
+        Puzzle::generate(self, rng, args)
+    }
+
+    fn diff(&self, args: &Args) -> Result<(), Error> {
+        diff::run(self, args)
+    }
+}
+
+/// Solve an input file while reading it, printing the answers at the end.
+fn run_stream<P: Puzzle>(puzzle: &P, filename: &str, args: &Args) -> Result<(), Error> {
+    for option in ["--bench", "--record", "--render", "--verify"] {
+        if args.has_flag(option) || args.value(option).is_some() {
+            return Err(ArgError::NotWithStream(option.to_string()).into());
+        }
+    }
+    let format: Format = args.parsed_value("--format")?.unwrap_or_default();
+    let parts = puzzle.parts(args)?;
+    let mut reader = input::open(filename)?;
+    let answers = puzzle.solve_stream(&mut reader, args, parts)
+        .map_err(|err| err.in_file(input::display_name(filename)))?;
+    for (part, answer) in answers {
+        output::print_answer(format, P::DAY, part, &answer);
+    }
+    Ok(())
+}
+
+/// Solve several input files in parallel and print a table of the answers.
+fn run_batch<P: Puzzle>(puzzle: &P, args: &Args) -> Result<(), Error> {
+    for option in ["--bench", "--log", "--record", "--render", "--trace", "--verify"] {
+        if args.has_flag(option) || args.value(option).is_some() {
+            return Err(ArgError::NotWithBatch(option.to_string()).into());
+        }
+    }
+    if puzzle.streams(args) {
+        return Err(ArgError::NotWithBatch("streaming".to_string()).into());
+    }
+    let format: Format = args.parsed_value("--format")?.unwrap_or_default();
+    let parts = puzzle.parts(args)?;
+    let workers = args.parsed_value_in("--jobs", 1..)?.unwrap_or_else(batch::default_workers);
+    let files = batch::expand(args.positional())?;
+    let rows = batch::solve_all(puzzle, &files, args, parts, workers);
+    match batch::print_rows(format, P::DAY, parts, &rows) {
+        0 => Ok(()),
+        failures => Err(Error::Batch(failures)),
+    }
+}
+
+/// Time parsing and each selected part over the given number of iterations.
+fn benchmark<P: Puzzle>(puzzle: &P, text: &str, args: &Args, parts: Parts, iterations: usize)
+    -> Result<Bench, Error>
+{
+    let mut bench = Bench::new(iterations);
+    for _ in 0..iterations {
+        let (input, duration) = bench::time(|| puzzle.parse(black_box(text), args));
+        let input = input?;
+        bench.record("parse", duration);
+        if parts.contains(1) {
+            bench.record("part 1", bench::time(|| black_box(puzzle.part1(&input))).1);
+        }
+        if parts.contains(2) {
+            bench.record("part 2", bench::time(|| black_box(puzzle.part2(&input))).1);
+        }
+    }
+    Ok(bench)
+}
+
+/// Flags that every solution accepts.
+pub const COMMON_FLAGS: &[&str] = &["--record", "--trace", "--verify", "--watch"];
+
+/// Options that every solution accepts, in the format used by `Args::parse`.
+pub const COMMON_OPTIONS: &[&str] = &["--answers", "--bench", "--format", "--jobs", "--log", "--part"];
+
+const COMMON_USAGE: &str = "
+COMMON OPTIONS:
+ --part PART: Run only part 1 or 2 of the puzzle, or both (the default)
+ --format FORMAT: Print answers as text (the default) or as json, with one
+                  object per part, and one per reported fact with a null part
+ --bench N: Instead of printing the answers, run the puzzle N times and print
+            the min, median and max time taken to parse and to solve each
+            part
+ --record: Save the answers as the known answers for this input (kept apart
+           for each use of the day's options that change the answers)
+ --verify: Check the answers against the ones saved with --record, printing
+           PASS or FAIL for each part. Exits with status 1 if any part fails
+ --answers DIR: Directory holding the known answers (default: answers)
+ --jobs N: Number of threads to solve inputs on when there are several
+          (default: one per CPU)
+ --trace: Explain each step the solver takes on standard error (same as
+          --log trace)
+ --log LEVEL: How much to explain on standard error: warn (the default),
+              info, debug or trace
+ --watch: Keep running, and solve again whenever an input file changes
+
+Without FILENAME, the input downloaded by `aoc fetch` is used if there is one.
+Given several FILENAMEs or a directory, every file is solved in parallel and
+the answers and timings are printed as a table (or as json, with one object
+per file and part).
+";
+
+/// Options that every input generator accepts.
+pub const COMMON_GEN_OPTIONS: &[&str] = &["--seed", "--size"];
+
+const COMMON_GEN_USAGE: &str = "
+COMMON OPTIONS:
+ --seed SEED: Seed for the random number generator. Without it, a seed is
+              picked from the clock and printed to standard error
+ --size SIZE: How much input to generate (see above for what it counts)
+";
+
+/// Print a solution's usage message, followed by the options every solution
+/// accepts, and how --render works if the solution takes it.
+pub fn print_usage(solver: &dyn Solver, argv0: &str) {
+    usage(argv0, solver.usage());
+    print!("{}", COMMON_USAGE);
+    if solver.options().contains(&"--render") {
+        print!("{}", render::USAGE);
+    }
+}
+
+/// Print an input generator's usage message, followed by the options every
+/// generator accepts.
+pub fn print_gen_usage(solver: &dyn Solver, argv0: &str) {
+    usage(argv0, solver.gen_usage());
+    print!("{}", COMMON_GEN_USAGE);
+}
+
+/// The description portion of a usage message: everything after the synopsis
+/// up to the list of options or the puzzle URL.
+pub fn description(usage: &str) -> String {
+    usage.lines()
+        .skip(1)
+        .take_while(|line| line.trim_end() != "OPTIONS:" && !line.starts_with("https://"))
+        .collect::<Vec<&str>>()
+        .join("\n")
+        .trim()
+        .to_string()
+}
+
+/// The first paragraph of a usage message's description.
+pub fn summary(usage: &str) -> String {
+    description(usage)
+        .split("\n\n")
+        .next()
+        .unwrap_or_default()
+        .replace('\n', " ")
+}
+
+/// Run a solution with the given arguments (not including the program name)
+/// and return the process exit code. The usage message is printed if -h was
+/// given or the arguments were invalid, and a diagnostic is printed if the
+/// input couldn't be read.
+pub fn run<I>(solver: &dyn Solver, argv0: &str, argv: I) -> i32
+    where I: IntoIterator<Item = String>
+{
+    let flags = [COMMON_FLAGS, solver.flags()].concat();
+    let options = [COMMON_OPTIONS, solver.options()].concat();
+    let print = || print_usage(solver, argv0);
+    let args = match Args::parse(argv, &flags, &options) {
+        Ok(args) => args,
+        Err(err) => return report(argv0, &err.into(), print),
+    };
+    if args.wants_help() {
+        print();
+        return 0;
+    }
+    if args.has_flag("--watch") {
+        return watch::main(solver, argv0, &args, print);
+    }
+    match solver.run(&args) {
+        Ok(()) => 0,
+        Err(err) => report(argv0, &err, print),
+    }
+}
+
+/// Run a solution's input generator with the given arguments and return the
+/// process exit code. The generated input is printed to standard output.
+pub fn gen<I>(solver: &dyn Solver, argv0: &str, argv: I) -> i32
+    where I: IntoIterator<Item = String>
+{
+    let options = [COMMON_GEN_OPTIONS, solver.gen_options()].concat();
+    let print = || print_gen_usage(solver, argv0);
+    let args = match Args::parse(argv, &[], &options) {
+        Ok(args) => args,
+        Err(err) => return report(argv0, &err.into(), print),
+    };
+    if args.wants_help() {
+        print();
+        return 0;
+    }
+    if let Some(arg) = args.positional().first() {
+        return report(argv0, &ArgError::UnexpectedArgument(arg.clone()).into(), print);
+    }
+    let seed = match args.parsed_value("--seed") {
+        Ok(seed) => seed,
+        Err(err) => return report(argv0, &err.into(), print),
+    };
+    let rng_seed = seed.unwrap_or_else(Rng::time_seed);
+    match solver.generate(&mut Rng::new(rng_seed), &args) {
+        Ok(text) => {
+            if seed.is_none() {
+                eprintln!("seed: {}", rng_seed);
+            }
+            print!("{}", text);
+            0
+        },
+        Err(err) => report(argv0, &err, print),
+    }
+}
+
+/// The --size given to an input generator, or `default` without it. The size
+/// must be at least 1.
+pub fn gen_size(args: &Args, default: usize) -> Result<usize, Error> {
+    Ok(args.parsed_value_in("--size", 1..)?.unwrap_or(default))
+}
+
+/// Print an error the appropriate way for its kind and return the exit code.
+/// Argument errors are followed by the usage message from `print_usage`.
+pub(crate) fn report(argv0: &str, err: &Error, print_usage: impl Fn()) -> i32 {
+    match err {
+        Error::Args(err) => {
+            eprintln!("{}: {}", argv0, err);
+            print_usage();
+            2
+        },
+        err => {
+            eprintln!("{}", err);
+            1
+        },
+    }
+}
+
+/// Entry point for a day's standalone binary.
+pub fn main(solver: &dyn Solver) {
+    let mut argv = env::args();
+    let argv0 = argv.next().unwrap_or_default();
+    let code = run(solver, &argv0, argv);
+    if code != 0 {
+        std::process::exit(code);
+    }
+}
@@ -0,0 +1,138 @@
+use std::fs;
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+use crate::bench::{self, Short};
+use crate::input::STDIN;
+use crate::solver::{self, Solver};
+use crate::{batch, fetch, ArgError, Args, Error};
+
+/// How often to look at the input files for changes.
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// What a file looked like when last checked: its modification time and
+/// size, or `None` if it couldn't be read, e.g. while an editor replaces it.
+type Stamp = Option<(SystemTime, u64)>;
+
+/// The files being watched, each with its stamp.
+type Snapshot = Vec<(String, Stamp)>;
+
+fn stamp(file: &str) -> Stamp {
+    let metadata = fs::metadata(file).ok()?;
+    Some((metadata.modified().ok()?, metadata.len()))
+}
+
+/// Stamp every file among `paths`, looking inside directories so that files
+/// added to them are noticed too.
+fn snapshot(paths: &[String]) -> Snapshot {
+    let files = batch::expand(paths).unwrap_or_else(|_| paths.to_vec());
+    files.into_iter().map(|file| {
+        let stamp = stamp(&file);
+        (file, stamp)
+    }).collect()
+}
+
+/// The files that were added, removed or modified between two snapshots.
+fn changed(before: &Snapshot, after: &Snapshot) -> Vec<String> {
+    let mut files: Vec<String> = after.iter()
+        .filter(|&entry| !before.contains(entry))
+        .map(|(file, _)| file.clone())
+        .collect();
+    files.extend(before.iter()
+        .filter(|(file, _)| !after.iter().any(|(other, _)| other == file))
+        .map(|(file, _)| file.clone()));
+    files
+}
+
+/// The paths to watch: the FILENAMEs given, or the cached input without any.
+fn watched_paths(day: u32, args: &Args) -> Result<Vec<String>, Error> {
+    if args.positional().iter().any(|path| path == STDIN) {
+        return Err(ArgError::NotWithStdin("--watch".to_string()).into());
+    }
+    if !args.positional().is_empty() {
+        return Ok(args.positional().to_vec());
+    }
+    let cached = fetch::cached_input(day).ok_or(ArgError::MissingArgument)?;
+    Ok(vec![cached.display().to_string()])
+}
+
+/// Wait until the files change, then until they stop changing for a moment
+/// so that a file isn't read while it's half written. Returns the files that
+/// changed and the new snapshot.
+fn wait_for_change(paths: &[String], seen: &Snapshot) -> (Vec<String>, Snapshot) {
+    loop {
+        thread::sleep(POLL_INTERVAL);
+        let mut current = snapshot(paths);
+        if current == *seen {
+            continue;
+        }
+        loop {
+            thread::sleep(POLL_INTERVAL);
+            let settled = snapshot(paths);
+            if settled == current {
+                break;
+            }
+            current = settled;
+        }
+        return (changed(seen, &current), current);
+    }
+}
+
+/// Run a solution, then run it again each time its input files change, until
+/// interrupted. Errors in the input are reported without stopping, but
+/// argument errors end the watch, returning the exit code.
+pub fn main(solver: &dyn Solver, argv0: &str, args: &Args, print_usage: impl Fn()) -> i32 {
+    let paths = match watched_paths(solver.day(), args) {
+        Ok(paths) => paths,
+        Err(err) => return solver::report(argv0, &err, print_usage),
+    };
+    let mut seen = snapshot(&paths);
+    loop {
+        let (result, duration) = bench::time(|| solver.run(args));
+        match result {
+            Ok(()) => eprintln!("Finished in {}", Short(duration)),
+            Err(err @ Error::Args(_)) => return solver::report(argv0, &err, print_usage),
+            Err(err) => {
+                solver::report(argv0, &err, &print_usage);
+                eprintln!("Failed after {}", Short(duration));
+            },
+        }
+        eprintln!("Watching {} for changes (press Ctrl-C to stop)", paths.join(", "));
+        let (files, current) = wait_for_change(&paths, &seen);
+        seen = current;
+        eprintln!();
+        eprintln!("{} changed, running again", files.join(", "));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(file: &str, secs: u64) -> (String, Stamp) {
+        (file.to_string(), Some((SystemTime::UNIX_EPOCH + Duration::from_secs(secs), 10)))
+    }
+
+    #[test]
+    fn finds_changed_files() {
+        let before = vec![entry("a.txt", 1), entry("b.txt", 1)];
+        assert!(changed(&before, &before).is_empty());
+        let after = vec![entry("a.txt", 2), entry("c.txt", 1)];
+        assert_eq!(changed(&before, &after), vec!["a.txt", "c.txt", "b.txt"]);
+        let unreadable = vec![("a.txt".to_string(), None), entry("b.txt", 1)];
+        assert_eq!(changed(&before, &unreadable), vec!["a.txt"]);
+    }
+
+    #[test]
+    fn notices_files_added_to_a_directory() {
+        let dir = std::env::temp_dir().join(format!("aoc-watch-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("one.txt"), "1\n").unwrap();
+        let paths = vec![dir.display().to_string()];
+        let before = snapshot(&paths);
+        fs::write(dir.join("two.txt"), "2\n").unwrap();
+        let after = snapshot(&paths);
+        fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(changed(&before, &after), vec![dir.join("two.txt").display().to_string()]);
+    }
+}